@@ -1,7 +1,9 @@
 use pygmentize::{highlight, Terminal256Formatter};
 
 fn main() {
-    // See also `TerminalFormatter` and `TerminalTrueColorFormatter`
+    // See also `TerminalFormatter` and `TerminalTrueColorFormatter`.
+    // For a light terminal, use a light style, e.g. `"solarized-light"`,
+    // or `TerminalFormatter` with `background: Background::Light`
     let fmt = Terminal256Formatter {
         line_numbers: false,
         ..Terminal256Formatter::default()
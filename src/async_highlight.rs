@@ -0,0 +1,85 @@
+use std::io;
+use std::sync::Arc;
+
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::transform::apply_transforms;
+use crate::truncate::truncate_lines;
+use crate::{
+    check_empty_output, fmt_args, output_to_string, spawn_with, PygmentizeError,
+    PygmentizeFormatter,
+};
+
+/// Same as [`highlight()`](crate::highlight), but `pygmentize` is awaited
+/// instead of blocking the thread, e.g. for use in async web servers.
+///
+/// _This requires the `async` feature, and must be called
+/// from within a Tokio runtime._
+///
+/// Post-processing runs on the calling task, i.e. options which
+/// require additional `pygmentize` calls, e.g.
+/// [`HtmlFormatter::scoped_style`](crate::HtmlFormatter::scoped_style),
+/// block briefly.
+///
+/// If the future is dropped, then `pygmentize` is killed.
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::HtmlFormatter;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let html = pygmentize::highlight_async("fn main() {}", Some("rust"), &HtmlFormatter::default()).await?;
+/// assert!(html.contains(r#"<span class="k">fn</span>"#));
+/// # Ok(())
+/// # }
+/// ```
+pub async fn highlight_async<F>(
+    code: impl AsRef<str>,
+    lang: Option<&str>,
+    fmt: &F,
+) -> Result<String, PygmentizeError>
+where
+    F: PygmentizeFormatter,
+{
+    let code: Arc<str> = Arc::from(truncate_lines(code.as_ref()));
+    fmt.validate_input(&code)?;
+
+    let args = fmt_args(lang, fmt)?;
+    let mut child = spawn_with(None, args.iter().map(|arg| &**arg), true, |cmd| {
+        Command::from(cmd).kill_on_drop(true).spawn()
+    })?;
+
+    // Written concurrently with reading stdout and stderr,
+    // such that `pygmentize` does not block on a full pipe
+    let mut stdin = child.stdin.take().expect("expected stdin");
+    let stdin = tokio::spawn({
+        let code = Arc::clone(&code);
+        async move {
+            let res = stdin.write_all(code.as_bytes()).await;
+            drop(stdin);
+            res
+        }
+    });
+
+    let output = child
+        .wait_with_output()
+        .await
+        .map_err(PygmentizeError::Process)?;
+
+    match stdin.await {
+        Ok(Ok(())) => {}
+        // `pygmentize` exiting before reading all of stdin is
+        // reported through its exit status instead
+        Ok(Err(err)) if err.kind() == io::ErrorKind::BrokenPipe => {}
+        Ok(Err(err)) => return Err(PygmentizeError::Process(err)),
+        Err(err) => return Err(PygmentizeError::Process(io::Error::other(err))),
+    }
+
+    let output = output_to_string(output)?;
+    check_empty_output(code.as_bytes(), output.as_bytes())?;
+    let output = fmt.post_process(&code, output)?;
+    Ok(apply_transforms(output))
+}
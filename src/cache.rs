@@ -0,0 +1,89 @@
+use std::any::TypeId;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+use crate::{highlight, PygmentizeError, PygmentizeFormatter};
+
+static CACHE: Mutex<Option<HashMap<CacheKey, Arc<str>>>> = Mutex::new(None);
+
+#[derive(PartialEq, Eq, Hash, Debug)]
+struct CacheKey {
+    fmt_type: TypeId,
+    fmt_hash: u64,
+    lang: Option<String>,
+    code: String,
+}
+
+/// Same as [`highlight()`], but the result is cached in memory, and
+/// subsequent calls with the same `code`, `lang`, and `fmt` return
+/// the cached result. The result is returned as an `Arc<str>`, such
+/// that it can be shared cheaply, without copying the string.
+///
+/// Errors are not cached.
+///
+/// The cache is not invalidated by e.g. [`set_bin_path()`](crate::set_bin_path)
+/// [`add_transform()`](crate::add_transform), or
+/// [`set_max_line_length()`](crate::set_max_line_length), call [`clear_cache()`]
+/// after changing those. The cache is unbounded, so also call
+/// [`clear_cache()`] if highlighting a lot of distinct inputs.
+///
+/// # Example
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use pygmentize::HtmlFormatter;
+///
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let fmt = HtmlFormatter::default();
+///
+/// let a = pygmentize::highlight_cached("fn main() {}", Some("rust"), &fmt)?;
+/// let b = pygmentize::highlight_cached("fn main() {}", Some("rust"), &fmt)?;
+/// assert!(Arc::ptr_eq(&a, &b));
+/// # Ok(())
+/// # }
+/// ```
+pub fn highlight_cached<F>(
+    code: impl AsRef<str>,
+    lang: Option<&str>,
+    fmt: &F,
+) -> Result<Arc<str>, PygmentizeError>
+where
+    F: PygmentizeFormatter + Hash + 'static,
+{
+    let code = code.as_ref();
+
+    let mut hasher = DefaultHasher::new();
+    fmt.hash(&mut hasher);
+
+    let key = CacheKey {
+        fmt_type: TypeId::of::<F>(),
+        fmt_hash: hasher.finish(),
+        lang: lang.map(str::to_owned),
+        code: code.to_owned(),
+    };
+
+    if let Some(cache) = CACHE.lock().unwrap().as_ref() {
+        if let Some(output) = cache.get(&key) {
+            return Ok(Arc::clone(output));
+        }
+    }
+
+    // The lock is not held while highlighting, such that other
+    // threads are not blocked by it
+    let output: Arc<str> = Arc::from(highlight(code, lang, fmt)?);
+
+    CACHE
+        .lock()
+        .unwrap()
+        .get_or_insert_with(HashMap::new)
+        .insert(key, Arc::clone(&output));
+
+    Ok(output)
+}
+
+/// Remove all results cached by [`highlight_cached()`].
+pub fn clear_cache() {
+    *CACHE.lock().unwrap() = None;
+}
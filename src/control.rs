@@ -0,0 +1,163 @@
+use std::ffi::OsStr;
+use std::io::{self, Read, Write};
+use std::process::Output;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::{highlight_with, output_to_bytes, spawn_with, PygmentizeError, PygmentizeFormatter};
+
+/// How often the child is checked for having exited,
+/// been cancelled, or exceeded the timeout.
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Controls for terminating `pygmentize` early,
+/// see [`highlight_controlled()`].
+#[derive(Clone, Copy, Default, Debug)]
+pub struct Control<'a> {
+    /// Kill `pygmentize` if it has not finished within the duration,
+    /// and return [`PygmentizeError::Timeout`].
+    pub timeout: Option<Duration>,
+    /// Kill `pygmentize` when the flag is set to `true`,
+    /// and return [`PygmentizeError::Cancelled`].
+    pub cancel: Option<&'a AtomicBool>,
+}
+
+impl Control<'_> {
+    /// Returns `true` if there is a timeout or cancel flag.
+    pub(crate) fn is_active(&self) -> bool {
+        self.timeout.is_some() || self.cancel.is_some()
+    }
+}
+
+/// Same as [`highlight()`](crate::highlight), but `pygmentize` is killed
+/// if it exceeds `control.timeout` or if `control.cancel` is set,
+/// whichever happens first.
+///
+/// # Example
+///
+/// ```rust
+/// use std::sync::atomic::AtomicBool;
+/// use std::time::Duration;
+/// use pygmentize::{Control, HtmlFormatter};
+///
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// // Set by e.g. another thread when the user types again
+/// let cancel = AtomicBool::new(false);
+///
+/// let control = Control {
+///     timeout: Some(Duration::from_secs(5)),
+///     cancel: Some(&cancel),
+/// };
+///
+/// let html = pygmentize::highlight_controlled("fn main() {}", Some("rust"), &HtmlFormatter::default(), control)?;
+/// println!("{html}");
+/// # Ok(())
+/// # }
+/// ```
+pub fn highlight_controlled<F>(
+    code: impl AsRef<str>,
+    lang: Option<&str>,
+    fmt: &F,
+    control: Control<'_>,
+) -> Result<String, PygmentizeError>
+where
+    F: PygmentizeFormatter,
+{
+    highlight_with(None, code.as_ref(), lang, fmt, None, control)
+}
+
+/// Same as `run_cmd_with_bin()`, but stdin is written and stdout and
+/// stderr are read on separate threads, while the child is polled
+/// for `control`.
+pub(crate) fn run_cmd_controlled<I, S>(
+    bin: Option<&OsStr>,
+    args: I,
+    stdin: Option<&[u8]>,
+    control: Control<'_>,
+) -> Result<Vec<u8>, PygmentizeError>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let deadline = control.timeout.map(|timeout| Instant::now() + timeout);
+
+    let mut child = spawn_with(bin, args, stdin.is_some(), |mut cmd| cmd.spawn())?;
+
+    // The threads are not scoped, such that they can be detached if the
+    // child is killed, as e.g. a grandchild might still hold the pipes
+    let stdin_thread = match (child.stdin.take(), stdin) {
+        (Some(mut pipe), Some(data)) => {
+            let data = data.to_owned();
+            Some(thread::spawn(move || pipe.write_all(&data)))
+        }
+        _ => None,
+    };
+    let stdout_thread = spawn_reader(child.stdout.take().expect("expected stdout"));
+    let stderr_thread = spawn_reader(child.stderr.take().expect("expected stderr"));
+
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) => {}
+            Err(err) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(PygmentizeError::Process(err));
+            }
+        }
+
+        let err = if control
+            .cancel
+            .is_some_and(|cancel| cancel.load(Ordering::Relaxed))
+        {
+            PygmentizeError::Cancelled
+        } else if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            PygmentizeError::Timeout
+        } else {
+            thread::sleep(POLL_INTERVAL);
+            continue;
+        };
+
+        // Errors are ignored, as the child might have exited in the meantime
+        let _ = child.kill();
+        let _ = child.wait();
+        return Err(err);
+    };
+
+    if let Some(stdin_thread) = stdin_thread {
+        match stdin_thread.join().expect("stdin thread panicked") {
+            // `pygmentize` exiting before reading all of stdin is
+            // reported through its exit status instead
+            Err(err) if err.kind() == io::ErrorKind::BrokenPipe => {}
+            res => res.map_err(PygmentizeError::Process)?,
+        }
+    }
+
+    let stdout = join_reader(stdout_thread)?;
+    let stderr = join_reader(stderr_thread)?;
+
+    output_to_bytes(Output {
+        status,
+        stdout,
+        stderr,
+    })
+}
+
+fn spawn_reader<R>(mut pipe: R) -> JoinHandle<io::Result<Vec<u8>>>
+where
+    R: Read + Send + 'static,
+{
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        pipe.read_to_end(&mut buf)?;
+        Ok(buf)
+    })
+}
+
+fn join_reader(thread: JoinHandle<io::Result<Vec<u8>>>) -> Result<Vec<u8>, PygmentizeError> {
+    thread
+        .join()
+        .expect("reader thread panicked")
+        .map_err(PygmentizeError::Process)
+}
@@ -0,0 +1,100 @@
+use std::fmt;
+
+use crate::{token_type_histogram, PygmentizeError};
+
+/// An issue found by [`validate_corpus()`].
+#[derive(Debug)]
+pub struct CorpusIssue {
+    /// The index of the item in the corpus.
+    pub index: usize,
+    pub kind: CorpusIssueKind,
+    /// A description of the issue, e.g. the error
+    /// output by `pygmentize` or the invalid tokens.
+    pub message: String,
+}
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum CorpusIssueKind {
+    /// No lexer exists for the language.
+    UnknownLanguage,
+    /// The lexer produced error tokens, i.e. parts of the
+    /// code are not valid for the language.
+    LexError,
+    /// The lexer produced no tokens for non-empty code.
+    EmptyOutput,
+    /// Executing `pygmentize` failed for another reason.
+    Error,
+}
+
+impl fmt::Display for CorpusIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            CorpusIssueKind::UnknownLanguage => "unknown language",
+            CorpusIssueKind::LexError => "lex error",
+            CorpusIssueKind::EmptyOutput => "empty output",
+            CorpusIssueKind::Error => "error",
+        };
+        write!(f, "item {}: {kind}: {}", self.index, self.message)
+    }
+}
+
+/// Highlights each item of `items`, i.e. `(code, lang)` pairs, and
+/// returns the issues found, e.g. to check code samples before
+/// publishing. An issue with one item does not abort the others.
+///
+/// Besides items for which highlighting fails, items are reported
+/// for which the lexer produced error tokens (`Token.Error`), which
+/// Pygments otherwise silently outputs as is.
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::CorpusIssueKind;
+///
+/// let items = [
+///     (String::from("fn main() {}"), Some(String::from("rust"))),
+///     (String::from("x = $"), Some(String::from("python"))),
+///     (String::from("x = 1"), Some(String::from("not-a-language"))),
+/// ];
+///
+/// let issues = pygmentize::validate_corpus(&items);
+/// assert_eq!(issues.len(), 2);
+/// assert_eq!((issues[0].index, issues[0].kind), (1, CorpusIssueKind::LexError));
+/// assert_eq!((issues[1].index, issues[1].kind), (2, CorpusIssueKind::UnknownLanguage));
+/// ```
+pub fn validate_corpus(items: &[(String, Option<String>)]) -> Vec<CorpusIssue> {
+    items
+        .iter()
+        .enumerate()
+        .filter_map(|(index, (code, lang))| {
+            let (kind, message) = validate_item(code, lang.as_deref())?;
+            Some(CorpusIssue {
+                index,
+                kind,
+                message,
+            })
+        })
+        .collect()
+}
+
+fn validate_item(code: &str, lang: Option<&str>) -> Option<(CorpusIssueKind, String)> {
+    let histogram = match token_type_histogram(code, lang) {
+        Ok(histogram) => histogram,
+        Err(err @ PygmentizeError::UnknownLexer(_)) => {
+            return Some((CorpusIssueKind::UnknownLanguage, err.to_string()));
+        }
+        Err(err) => return Some((CorpusIssueKind::Error, err.to_string())),
+    };
+
+    if let Some(&count) = histogram.get("Token.Error") {
+        let message = format!("{count} error token(s)");
+        return Some((CorpusIssueKind::LexError, message));
+    }
+
+    if histogram.is_empty() && !code.is_empty() {
+        let message = String::from("no tokens for non-empty code");
+        return Some((CorpusIssueKind::EmptyOutput, message));
+    }
+
+    None
+}
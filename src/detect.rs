@@ -0,0 +1,137 @@
+use crate::{highlight, PygmentizeError, PygmentizeFormatter};
+
+/// Vim only checks the first and last 5 lines for modelines.
+const MODELINE_LINES: usize = 5;
+
+/// Applies syntax highlighting to `code`, where the language is
+/// detected from the contents of `code`, in the following order:
+///
+/// 1. A vim modeline, e.g. `# vim: ft=ruby` or `// vim: set filetype=rust:`,
+///    within the first or last 5 lines.
+/// 2. An emacs modeline, e.g. `# -*- mode: ruby -*-` or `# -*- ruby -*-`,
+///    within the first 2 lines.
+/// 3. A shebang, e.g. `#!/usr/bin/env python3`.
+/// 4. Otherwise, the language is guessed by `pygmentize`,
+///    see [`highlight()`].
+///
+/// If Pygments has no lexer for the detected language, e.g. for
+/// `#!/usr/bin/tclsh`, then the language is also guessed.
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::HtmlFormatter;
+///
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let code = r#"# vim: ft=ruby
+/// puts "Hello, world!"
+/// "#;
+///
+/// let html = pygmentize::highlight_smart(code, &HtmlFormatter::default())?;
+/// println!("{html}");
+/// # Ok(())
+/// # }
+/// ```
+pub fn highlight_smart<F>(code: impl AsRef<str>, fmt: &F) -> Result<String, PygmentizeError>
+where
+    F: PygmentizeFormatter,
+{
+    let code = code.as_ref();
+    match detect_lang(code) {
+        Some(lang) => match highlight(code, &lang, fmt) {
+            Err(PygmentizeError::UnknownLexer(_)) => highlight(code, None, fmt),
+            res => res,
+        },
+        None => highlight(code, None, fmt),
+    }
+}
+
+fn detect_lang(code: &str) -> Option<String> {
+    let lines = code.lines().collect::<Vec<_>>();
+
+    let first = lines.iter().take(MODELINE_LINES);
+    let last = lines.iter().skip(MODELINE_LINES).rev().take(MODELINE_LINES);
+    if let Some(lang) = first.chain(last).find_map(|line| parse_vim_modeline(line)) {
+        return Some(lang);
+    }
+
+    if let Some(lang) = lines
+        .iter()
+        .take(2)
+        .find_map(|line| parse_emacs_modeline(line))
+    {
+        return Some(lang);
+    }
+
+    lines.first().and_then(|line| parse_shebang(line))
+}
+
+/// Parses e.g. `vim: ft=ruby`, `vim: set filetype=ruby:`, or `vi: syntax=ruby`.
+fn parse_vim_modeline(line: &str) -> Option<String> {
+    // Vim requires whitespace before the prefix, unless it is at the start of the line
+    let modeline = ["vim:", "vi:", "ex:"].iter().find_map(|prefix| {
+        line.match_indices(prefix)
+            .find(|&(i, _)| {
+                line[..i]
+                    .chars()
+                    .next_back()
+                    .is_none_or(char::is_whitespace)
+            })
+            .map(|(i, _)| &line[(i + prefix.len())..])
+    })?;
+
+    modeline
+        .split(|c: char| c.is_whitespace() || (c == ':'))
+        .filter_map(|opt| opt.split_once('='))
+        .find(|(key, _)| matches!(*key, "ft" | "filetype" | "syntax"))
+        .map(|(_, ft)| ft.to_owned())
+        .filter(|ft| !ft.is_empty())
+}
+
+/// Parses e.g. `-*- mode: ruby -*-`, `-*- ruby -*-`, or
+/// `-*- mode: ruby; coding: utf-8 -*-`.
+fn parse_emacs_modeline(line: &str) -> Option<String> {
+    let (_, rest) = line.split_once("-*-")?;
+    let (vars, _) = rest.split_once("-*-")?;
+    let vars = vars.trim();
+
+    let mode = if vars.contains(':') {
+        vars.split(';')
+            .filter_map(|var| var.split_once(':'))
+            .find(|(key, _)| key.trim().eq_ignore_ascii_case("mode"))
+            .map(|(_, mode)| mode.trim())?
+    } else {
+        vars
+    };
+
+    let mode = mode.to_ascii_lowercase();
+    let mode = mode.strip_suffix("-mode").unwrap_or(&mode);
+    match mode {
+        "" => None,
+        "c++" => Some(String::from("cpp")),
+        "js" | "js2" => Some(String::from("javascript")),
+        mode => Some(mode.to_owned()),
+    }
+}
+
+/// Parses e.g. `#!/bin/bash`, `#!/usr/bin/env python3`,
+/// or `#!/usr/bin/env -S node --flag`.
+fn parse_shebang(line: &str) -> Option<String> {
+    let line = line.strip_prefix("#!")?;
+
+    let mut args = line.split_whitespace();
+    let mut interpreter = args.next()?;
+    if interpreter.rsplit('/').next() == Some("env") {
+        interpreter = args.find(|arg| !arg.starts_with('-'))?;
+    }
+
+    let interpreter = interpreter.rsplit('/').next()?;
+    // e.g. `python3.11` -> `python`
+    let name = interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || (c == '.'));
+    match name {
+        "" => None,
+        "node" | "nodejs" | "deno" => Some(String::from("javascript")),
+        "sh" | "dash" | "ash" => Some(String::from("bash")),
+        name => Some(name.to_owned()),
+    }
+}
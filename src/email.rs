@@ -0,0 +1,125 @@
+use std::borrow::Cow;
+
+use crate::formatters::Options;
+use crate::highlighted::DEFAULT_STYLE;
+use crate::{highlight, PygmentizeError, PygmentizeFormatter};
+
+/// Spaces per tab, as tabs are not rendered consistently in emails.
+const TAB_WIDTH: usize = 4;
+
+const FONT_FAMILY: &str = "Consolas, Menlo, Monaco, 'Courier New', monospace";
+
+/// Applies syntax highlighting to `code` written in `lang`, and outputs
+/// HTML suitable for emails, using `style` or `"default"` if `None`.
+///
+/// Many email clients strip `<style>` elements and ignore external
+/// stylesheets, and e.g. Outlook renders `<div>` and `<pre>` poorly.
+/// So compared to [`HtmlFormatter`](crate::HtmlFormatter):
+///
+/// - All styles are inlined as `style` attributes, i.e. there are
+///   no classes.
+/// - The wrapping `<div>` is replaced with a single-cell
+///   `<table role="presentation">`, with the background color given
+///   both as `bgcolor` and `style`.
+/// - There is no `<pre>`, instead lines are separated by `<br>`,
+///   and spaces are replaced by `&nbsp;`, tabs by 4 `&nbsp;`.
+///   Spaces within tags are left as is.
+/// - The font is set explicitly to a monospace font.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let html = pygmentize::highlight_email("fn main() {}", Some("rust"), Some("monokai"))?;
+/// assert!(html.starts_with(r#"<table role="presentation""#));
+/// assert!(!html.contains("class="));
+/// assert!(!html.contains("<pre"));
+/// # Ok(())
+/// # }
+/// ```
+pub fn highlight_email(
+    code: impl AsRef<str>,
+    lang: Option<&str>,
+    style: Option<&str>,
+) -> Result<String, PygmentizeError> {
+    let style = style.unwrap_or(DEFAULT_STYLE);
+    highlight(code, lang, &EmailFormatter { style })
+}
+
+/// The `html` formatter with `noclasses`, where
+/// the output is made suitable for emails.
+struct EmailFormatter<'a> {
+    style: &'a str,
+}
+
+impl PygmentizeFormatter for EmailFormatter<'_> {
+    const SHORT_NAME: &'static str = "html";
+
+    fn options_str(&self) -> Option<Cow<'_, str>> {
+        let mut opts = Options::new();
+        opts.push("noclasses", "true");
+        opts.push("style", self.style);
+        opts.finish()
+    }
+
+    fn style(&self) -> Option<&str> {
+        Some(self.style)
+    }
+
+    fn post_process(&self, _code: &str, output: String) -> Result<String, PygmentizeError> {
+        Ok(to_email_html(&output))
+    }
+}
+
+fn to_email_html(html: &str) -> String {
+    // e.g. `<div class="highlight" style="background: #f8f8f8">`
+    let background = html
+        .split_once(r#"style="background: "#)
+        .and_then(|(_, rest)| rest.split_once('"'))
+        .map(|(background, _)| background.trim_end_matches(';'));
+
+    let code = html
+        .split_once("<pre")
+        .and_then(|(_, rest)| rest.split_once('>'))
+        .map_or(html, |(_, code)| code);
+    let code = code.rsplit_once("</pre>").map_or(code, |(code, _)| code);
+    let code = code.strip_prefix("<span></span>").unwrap_or(code);
+    let code = code.strip_suffix('\n').unwrap_or(code);
+
+    let mut out = String::with_capacity(code.len() * 2);
+    out.push_str(
+        r#"<table role="presentation" cellpadding="0" cellspacing="0" border="0" width="100%""#,
+    );
+    if let Some(background) = background {
+        out.push_str(&format!(r#" bgcolor="{background}""#));
+    }
+    out.push_str(r#" style="border-collapse: collapse;"#);
+    if let Some(background) = background {
+        out.push_str(&format!(" background: {background};"));
+    }
+    out.push_str(r#""><tr><td style="padding: 8px 12px; font-family: "#);
+    out.push_str(FONT_FAMILY);
+    out.push_str(r#"; font-size: 13px; line-height: 1.25;">"#);
+
+    let mut in_tag = false;
+    for c in code.chars() {
+        match c {
+            '<' => {
+                in_tag = true;
+                out.push(c);
+            }
+            '>' => {
+                in_tag = false;
+                out.push(c);
+            }
+            _ if in_tag => out.push(c),
+            ' ' => out.push_str("&nbsp;"),
+            '\t' => out.push_str(&"&nbsp;".repeat(TAB_WIDTH)),
+            '\n' => out.push_str("<br>\n"),
+            c => out.push(c),
+        }
+    }
+
+    out.push_str("</td></tr></table>\n");
+    out
+}
@@ -0,0 +1,195 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+use crate::html::code_range;
+use crate::{highlight, HtmlFormatter, PygmentizeError, PygmentizeFormatter};
+
+/// Applies syntax highlighting to `code` written in `outer_lang`, and
+/// then replaces the `embedded` byte ranges of `code` with the ranges
+/// highlighted as their own language, e.g. SQL within a Python string.
+///
+/// The spans of the outer code are closed before and reopened after
+/// each embedded range, such that the spans remain balanced.
+///
+/// The ranges must be sorted, not overlapping, and on `char` boundaries,
+/// otherwise [`PygmentizeError::InvalidOption`] is returned.
+///
+/// _Where Pygments has a composite lexer for the combination,
+/// e.g. `html+jinja`, then prefer that instead._
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::HtmlFormatter;
+///
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let code = r#"query = "SELECT * FROM users""#;
+/// let sql = code.find("SELECT").unwrap()..(code.len() - 1);
+///
+/// let html = pygmentize::highlight_embedded(code, Some("python"), &[(sql, "sql")], &HtmlFormatter::default())?;
+/// assert!(html.contains(r#"<span class="k">SELECT</span>"#));
+/// # Ok(())
+/// # }
+/// ```
+pub fn highlight_embedded(
+    code: impl AsRef<str>,
+    outer_lang: Option<&str>,
+    embedded: &[(Range<usize>, &str)],
+    fmt: &HtmlFormatter,
+) -> Result<String, PygmentizeError> {
+    let code = code.as_ref();
+    validate_ranges(code, embedded)?;
+
+    let outer = highlight(code, outer_lang, fmt)?;
+    if embedded.is_empty() {
+        return Ok(outer);
+    }
+
+    let inner = embedded
+        .iter()
+        .map(|(range, lang)| {
+            let inner = &code[range.clone()];
+            let mut html = highlight(inner, *lang, &SpansFormatter)?;
+            // The `html` formatter always ends the last line with a newline
+            if !inner.ends_with('\n') && html.ends_with('\n') {
+                html.pop();
+            }
+            Ok(html)
+        })
+        .collect::<Result<Vec<_>, PygmentizeError>>()?;
+
+    let Some(content) = code_range(&outer) else {
+        return Ok(outer);
+    };
+
+    let mut out = String::with_capacity(outer.len() + inner.iter().map(String::len).sum::<usize>());
+    out.push_str(&outer[..content.start]);
+    splice(
+        &mut out,
+        code,
+        &outer[content.clone()],
+        embedded.iter().map(|(range, _)| range).zip(&inner),
+    );
+    out.push_str(&outer[content.end..]);
+    Ok(out)
+}
+
+fn validate_ranges(code: &str, embedded: &[(Range<usize>, &str)]) -> Result<(), PygmentizeError> {
+    let mut prev_end = 0;
+    for (range, _) in embedded {
+        if (range.start > range.end) || (range.end > code.len()) {
+            return Err(PygmentizeError::InvalidOption(
+                "embedded",
+                format!("range {range:?} is out of bounds"),
+            ));
+        }
+        if !code.is_char_boundary(range.start) || !code.is_char_boundary(range.end) {
+            return Err(PygmentizeError::InvalidOption(
+                "embedded",
+                format!("range {range:?} is not on a char boundary"),
+            ));
+        }
+        if range.start < prev_end {
+            return Err(PygmentizeError::InvalidOption(
+                "embedded",
+                format!("range {range:?} overlaps or is not sorted"),
+            ));
+        }
+        prev_end = range.end;
+    }
+    Ok(())
+}
+
+/// Walks the highlighted `html` alongside the source `code`, copying
+/// `html` to `out`, except for the `ranges`, which are replaced by
+/// their highlighted `inner` output.
+fn splice<'a>(
+    out: &mut String,
+    code: &str,
+    html: &str,
+    mut ranges: impl Iterator<Item = (&'a Range<usize>, &'a String)>,
+) {
+    // The `<span>`s currently open in `html`
+    let mut open: Vec<&str> = Vec::new();
+    let mut next = ranges.next();
+    // Position in `code` of the next text char in `html`
+    let mut pos = skip_unrendered(code, 0);
+    // Within a range, the text of `html` is skipped until `end`
+    let mut skip_until = None;
+
+    let mut i = 0;
+    while i < html.len() {
+        if skip_until.is_none() {
+            if let Some((range, inner)) = next.filter(|(range, _)| pos >= range.start) {
+                for _ in &open {
+                    out.push_str("</span>");
+                }
+                out.push_str(inner);
+                skip_until = Some(range.end);
+                next = ranges.next();
+            }
+        }
+        if let Some(end) = skip_until {
+            if pos >= end {
+                skip_until = None;
+                for tag in &open {
+                    out.push_str(tag);
+                }
+                // Ranges can be adjacent
+                continue;
+            }
+        }
+
+        let rest = &html[i..];
+        let len = if rest.starts_with('<') {
+            let len = rest.find('>').map_or(rest.len(), |len| len + 1);
+            let tag = &rest[..len];
+            if tag.starts_with("<span") {
+                open.push(tag);
+            } else if tag == "</span>" {
+                open.pop();
+            }
+            len
+        } else {
+            let len = if rest.starts_with('&') {
+                rest.find(';').map_or(1, |len| len + 1)
+            } else {
+                rest.chars().next().map_or(1, char::len_utf8)
+            };
+            pos = code[pos..]
+                .chars()
+                .next()
+                .map_or(pos, |c| skip_unrendered(code, pos + c.len_utf8()));
+            len
+        };
+
+        if skip_until.is_none() {
+            out.push_str(&rest[..len]);
+        }
+        i += len;
+    }
+}
+
+/// Skips chars, which are not in the output of `pygmentize`,
+/// i.e. the leading newlines of `code`, and `\r` of `\r\n`.
+fn skip_unrendered(code: &str, mut pos: usize) -> usize {
+    if code[..pos].bytes().all(|b| b == b'\n') {
+        pos += code[pos..].bytes().take_while(|&b| b == b'\n').count();
+    }
+    if code[pos..].starts_with("\r\n") {
+        pos += 1;
+    }
+    pos
+}
+
+/// The `html` formatter, which outputs only the token `<span>`s, and
+/// where `code` is not stripped of leading and trailing newlines.
+struct SpansFormatter;
+
+impl PygmentizeFormatter for SpansFormatter {
+    const SHORT_NAME: &'static str = "html";
+
+    fn options_str(&self) -> Option<Cow<'_, str>> {
+        Some(Cow::Borrowed("ensurenl=false,nowrap=true,stripnl=false"))
+    }
+}
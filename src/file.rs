@@ -0,0 +1,132 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use crate::{highlight_named, PygmentizeError, PygmentizeFormatter};
+
+/// Applies syntax highlighting to all files within the directory `root`,
+/// recursively. The language of each file is guessed from its filename,
+/// see [`highlight_file()`].
+///
+/// Files and directories for which `filter` returns `false` are skipped,
+/// i.e. directories are not descended into. Symlinks are not followed.
+///
+/// Files are highlighted in parallel. An error highlighting one file
+/// does not abort the others, instead the per-file result is returned
+/// alongside the path. The returned `Vec` is sorted by path.
+///
+/// Returns [`PygmentizeError::Io`] if walking `root` fails.
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::HtmlFormatter;
+///
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let fmt = HtmlFormatter::default();
+/// let files = pygmentize::highlight_dir("src", &fmt, |path| {
+///     path.is_dir() || path.extension().map_or(false, |ext| ext == "rs")
+/// })?;
+///
+/// for (path, html) in files {
+///     match html {
+///         Ok(html) => println!("{}: {} bytes", path.display(), html.len()),
+///         Err(err) => eprintln!("{}: {err}", path.display()),
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn highlight_dir<F>(
+    root: impl AsRef<Path>,
+    fmt: &F,
+    filter: impl Fn(&Path) -> bool,
+) -> Result<Vec<(PathBuf, Result<String, PygmentizeError>)>, PygmentizeError>
+where
+    F: PygmentizeFormatter + Sync,
+{
+    let mut paths = Vec::new();
+    walk_dir(root.as_ref(), &filter, &mut paths).map_err(PygmentizeError::Io)?;
+    paths.sort();
+
+    let results = paths.iter().map(|_| Mutex::new(None)).collect::<Vec<_>>();
+    let next = AtomicUsize::new(0);
+
+    let workers = thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(paths.len());
+
+    thread::scope(|s| {
+        for _ in 0..workers {
+            s.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(path) = paths.get(i) else {
+                    break;
+                };
+                *results[i].lock().unwrap() = Some(highlight_file(path, fmt));
+            });
+        }
+    });
+
+    let files = paths
+        .into_iter()
+        .zip(results)
+        .map(|(path, res)| {
+            let res = res.into_inner().unwrap().expect("expected result");
+            (path, res)
+        })
+        .collect();
+    Ok(files)
+}
+
+/// Reads the file at `path` and applies syntax highlighting to it,
+/// where the lexer is guessed from the filename, e.g. `main.rs` uses
+/// the Rust lexer, see [`guess_lexer_for_filename()`](crate::guess_lexer_for_filename).
+///
+/// Returns [`PygmentizeError::Io`] if reading the file fails.
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::HtmlFormatter;
+///
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let html = pygmentize::highlight_file("src/lib.rs", &HtmlFormatter::default())?;
+/// println!("{html}");
+/// # Ok(())
+/// # }
+/// ```
+pub fn highlight_file<F>(path: impl AsRef<Path>, fmt: &F) -> Result<String, PygmentizeError>
+where
+    F: PygmentizeFormatter,
+{
+    let path = path.as_ref();
+    let code = fs::read_to_string(path).map_err(PygmentizeError::Io)?;
+    highlight_named(code, path, fmt)
+}
+
+fn walk_dir(
+    dir: &Path,
+    filter: &impl Fn(&Path) -> bool,
+    paths: &mut Vec<PathBuf>,
+) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if !filter(&path) {
+            continue;
+        }
+
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            walk_dir(&path, filter, paths)?;
+        } else if file_type.is_file() {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use std::borrow::Cow;
+
+/// A filter applied to the token stream, before it is formatted.
+///
+/// See <https://pygments.org/docs/filters/> for available filters.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Filter {
+    pub name: Cow<'static, str>,
+    pub options: Vec<(Cow<'static, str>, Cow<'static, str>)>,
+}
+
+impl Filter {
+    /// A filter by its name, e.g. `"keywordcase"`, with
+    /// options added using [`option()`](Self::option).
+    ///
+    /// Neither the name nor the option keys and values can contain `,`,
+    /// the name cannot contain `:`, and the keys cannot contain `=`,
+    /// otherwise [`PygmentizeError::InvalidOption`] is returned when
+    /// used, e.g. in [`LexerOptions::filters`].
+    ///
+    /// ```rust
+    /// use pygmentize::{Filter, HtmlFormatter, LexerOptions, Pygmentize};
+    ///
+    /// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+    /// let pygmentize = Pygmentize::new().lexer_options(LexerOptions {
+    ///     filters: vec![Filter::new("keywordcase").option("case", "upper")],
+    ///     ..LexerOptions::default()
+    /// });
+    ///
+    /// let html = pygmentize.highlight("select 1", Some("sql"), &HtmlFormatter::default())?;
+    /// assert!(html.contains(r#"<span class="k">SELECT</span>"#));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`PygmentizeError::InvalidOption`]: crate::PygmentizeError::InvalidOption
+    /// [`LexerOptions::filters`]: crate::LexerOptions::filters
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            name: name.into(),
+            options: Vec::new(),
+        }
+    }
+
+    /// Returns `self` with the option `key=value` added.
+    pub fn option(
+        mut self,
+        key: impl Into<Cow<'static, str>>,
+        value: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        self.options.push((key.into(), value.into()));
+        self
+    }
+
+    /// Merges consecutive tokens with the same token type in the
+    /// output stream, which reduces the size of e.g. HTML output.
+    ///
+    /// See <https://pygments.org/docs/filters/#TokenMergeFilter>
+    /// for more information.
+    pub fn token_merge() -> Self {
+        Self {
+            name: Cow::Borrowed("tokenmerge"),
+            options: Vec::new(),
+        }
+    }
+
+    /// Highlights code tags in comments, i.e. `XXX`, `TODO`, `FIXME`,
+    /// `BUG`, and `NOTE`, as `Comment.Special` tokens.
+    ///
+    /// See <https://pygments.org/docs/filters/#CodeTagFilter>
+    /// for more information.
+    pub fn code_tags() -> Self {
+        Self {
+            name: Cow::Borrowed("codetagify"),
+            options: Vec::new(),
+        }
+    }
+
+    /// Same as [`code_tags()`](Self::code_tags), but highlights
+    /// `tags` instead of the default tags.
+    pub fn custom_code_tags<I, S>(tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let tags = tags
+            .into_iter()
+            .map(|tag| tag.as_ref().to_owned())
+            .collect::<Vec<_>>()
+            .join(" ");
+        Self {
+            name: Cow::Borrowed("codetagify"),
+            options: vec![(Cow::Borrowed("codetags"), Cow::Owned(tags))],
+        }
+    }
+
+    /// Returns the `name` or `key=value` which cannot be passed to `-F`,
+    /// as `pygmentize` splits the name at the first `:`, the options at
+    /// every `,`, and each option at the first `=`.
+    pub(crate) fn invalid_part(&self) -> Option<String> {
+        if self.name.contains([',', ':']) {
+            return Some(self.name.clone().into_owned());
+        }
+        self.options
+            .iter()
+            .find(|(key, value)| key.contains([',', '=']) || value.contains(','))
+            .map(|(key, value)| format!("{key}={value}"))
+    }
+
+    /// Returns the filter as an argument for `-F`, i.e. `name:key=value,...`.
+    pub(crate) fn to_arg(&self) -> String {
+        let mut arg = self.name.clone().into_owned();
+        for (i, (key, value)) in self.options.iter().enumerate() {
+            arg.push(if i == 0 { ':' } else { ',' });
+            arg.push_str(key);
+            arg.push('=');
+            arg.push_str(value);
+        }
+        arg
+    }
+}
@@ -1,13 +1,25 @@
 pub mod prelude {
     pub use super::{
-        HtmlFormatter, LatexFormatter, PygmentizeFormatter, SvgFormatter, Terminal256Formatter,
-        TerminalFormatter, TerminalTrueColorFormatter,
+        BBCodeFormatter, Background, HtmlFormatter, ImageFormat, ImageFormatter, IrcFormatter,
+        LatexFormatter, LineNumbers, PygmentizeFormatter, RawTokenFormatter, RtfFormatter,
+        SvgFormatter, Terminal256Formatter, TerminalFormatter, TerminalTrueColorFormatter,
     };
 }
 
 use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::fmt::{self, Write};
 
-use crate::{highlight, PygmentizeError};
+use crate::frozen::FrozenFormatter;
+use crate::highlighted::DEFAULT_STYLE;
+use crate::html::{
+    add_accessibility_attributes, add_jupyter_class, add_scoped_style, embed_source, escape_title,
+    expand_tabs, fold_regions, is_highlighted, line_spans_to_class, replace_root_element,
+    set_line_counter_start, to_html5, trim_line_trailing_whitespace,
+};
+use crate::version::version_with;
+use crate::{highlight, Filter, HlLines, PygmentizeError};
 
 /// Want to implement a formatter or add unsupported options?
 ///
@@ -18,6 +30,56 @@ pub trait PygmentizeFormatter: Sized {
 
     fn options_str(&self) -> Option<Cow<'_, str>>;
 
+    /// The name of the style used by the formatter, if any.
+    /// If `None`, Pygments uses the `"default"` style.
+    fn style(&self) -> Option<&str> {
+        None
+    }
+
+    /// Called before `pygmentize` is executed, to validate
+    /// the options of the formatter.
+    fn validate(&self) -> Result<(), PygmentizeError> {
+        Ok(())
+    }
+
+    /// Filters applied to the token stream, before it is formatted.
+    fn filters(&self) -> Cow<'_, [Filter]> {
+        Cow::Borrowed(&[])
+    }
+
+    /// Options passed as separate `-P key=value` arguments, instead of
+    /// as part of [`options_str()`](Self::options_str), such that their
+    /// values are passed verbatim, i.e. they can contain `,`.
+    fn verbatim_options(&self) -> Vec<(&'static str, &str)> {
+        Vec::new()
+    }
+
+    /// Called with the input `code`, before `pygmentize`
+    /// is executed by [`highlight()`](crate::highlight).
+    fn validate_input(&self, _code: &str) -> Result<(), PygmentizeError> {
+        Ok(())
+    }
+
+    /// Called with the input `code` and the output of `pygmentize`,
+    /// before it is returned from [`highlight()`](crate::highlight).
+    fn post_process(&self, _code: &str, output: String) -> Result<String, PygmentizeError> {
+        Ok(output)
+    }
+
+    /// Same as [`post_process()`](Self::post_process), but with the
+    /// `pygmentize` binary used for highlighting, e.g. with
+    /// [`Pygmentize::bin_path()`](crate::Pygmentize::bin_path), or
+    /// `None` for the global binary. Post-processing which executes
+    /// `pygmentize` must use it, instead of the global binary.
+    fn post_process_with(
+        &self,
+        _bin: Option<&OsStr>,
+        code: &str,
+        output: String,
+    ) -> Result<String, PygmentizeError> {
+        self.post_process(code, output)
+    }
+
     fn highlight(
         &self,
         code: impl AsRef<str>,
@@ -25,30 +87,549 @@ pub trait PygmentizeFormatter: Sized {
     ) -> Result<String, PygmentizeError> {
         highlight(code, lang, self)
     }
+
+    /// Computes [`options_str()`](Self::options_str) once, such that
+    /// it is not recomputed for every call, e.g. when highlighting
+    /// many inputs with the same formatter.
+    ///
+    /// ```rust
+    /// use pygmentize::{HtmlFormatter, PygmentizeFormatter};
+    ///
+    /// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+    /// let fmt = HtmlFormatter {
+    ///     line_numbers: true,
+    ///     ..HtmlFormatter::default()
+    /// };
+    /// let fmt = fmt.frozen();
+    ///
+    /// for code in ["let a = 1;", "let b = 2;"] {
+    ///     let html = fmt.highlight(code, Some("rust"))?;
+    ///     println!("{html}");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn frozen(self) -> FrozenFormatter<Self> {
+        FrozenFormatter::new(self)
+    }
 }
 
-/// Format tokens as HTML 4 `<span>` tags.
+/// Format tokens as HTML 4 `<span>` tags, or as HTML5
+/// if [`html5`](HtmlFormatter::html5) is enabled.
 ///
 /// See <https://pygments.org/docs/formatters/#HtmlFormatter>
 /// for more information.
-#[derive(Clone, Debug)]
+#[derive(Clone, Hash, Debug)]
 pub struct HtmlFormatter {
-    /// Output line numbers.
+    /// Output line numbers, as configured by
+    /// [`line_number_mode`](Self::line_number_mode).
     pub line_numbers: bool,
+    /// The line number of the first line, e.g. the position of a
+    /// fragment within a larger file. Pygments defaults to `1`.
+    ///
+    /// Only has an effect if `line_numbers` or `copyable` is enabled,
+    /// otherwise the `linenostart` option is not passed at all.
+    /// Must be at least `1`, otherwise
+    /// [`PygmentizeError::InvalidOption`] is returned.
+    ///
+    /// ```rust
+    /// use pygmentize::{HtmlFormatter, PygmentizeError};
+    ///
+    /// # fn main() -> Result<(), PygmentizeError> {
+    /// let fmt = HtmlFormatter {
+    ///     line_numbers: true,
+    ///     line_number_start: Some(42),
+    ///     ..HtmlFormatter::default()
+    /// };
+    ///
+    /// let html = pygmentize::highlight("let x = 1;\nlet y = 2;", Some("rust"), &fmt)?;
+    /// assert!(html.contains(r#"<span class="normal">42</span>"#));
+    /// assert!(html.contains(r#"<span class="normal">43</span>"#));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub line_number_start: Option<usize>,
+    /// Prepend a comment with the version of Pygments
+    /// that produced the output, e.g. `<!-- pygments 2.15.1 -->`.
+    ///
+    /// _This requires an additional `pygmentize -V` call the
+    /// first time, see [`version()`](crate::version)._
+    pub version_comment: bool,
+    /// Return [`PygmentizeError::AlreadyHighlighted`] if the input
+    /// looks like it is already highlighted HTML, instead of
+    /// escaping and highlighting it a second time.
+    ///
+    /// _This is opt-in, as highlighting HTML source, which just
+    /// happens to contain e.g. `<span class="k">`, is legitimate._
+    ///
+    /// ```rust
+    /// use pygmentize::{HtmlFormatter, PygmentizeError};
+    ///
+    /// let fmt = HtmlFormatter {
+    ///     reject_highlighted: true,
+    ///     ..HtmlFormatter::default()
+    /// };
+    ///
+    /// let html = r#"<div class="highlight"><pre><span></span><span class="k">fn</span>"#;
+    ///
+    /// let res = pygmentize::highlight(html, Some("html"), &fmt);
+    /// assert!(matches!(res, Err(PygmentizeError::AlreadyHighlighted)));
+    /// ```
+    pub reject_highlighted: bool,
+    /// Output line numbers in a separate table cell from the code,
+    /// such that the code can be copied without the line numbers.
+    /// This implies `line_numbers`.
+    ///
+    /// The code cell is marked with a `data-code` attribute, such
+    /// that it can be targeted by e.g. a copy button, i.e. the
+    /// output has the following shape:
+    ///
+    /// ```html
+    /// <div class="highlight">
+    ///   <table class="highlighttable">
+    ///     <tr>
+    ///       <td class="linenos">
+    ///         <div class="linenodiv"><pre>...</pre></div>
+    ///       </td>
+    ///       <td class="code" data-code>
+    ///         <div><pre>...</pre></div>
+    ///       </td>
+    ///     </tr>
+    ///   </table>
+    /// </div>
+    /// ```
+    ///
+    /// Which can then be copied with e.g.:
+    ///
+    /// ```js
+    /// const code = block.querySelector("[data-code] pre").innerText;
+    /// navigator.clipboard.writeText(code);
+    /// ```
+    pub copyable: bool,
+    /// Merge consecutive tokens of the same type into a single
+    /// `<span>`, which reduces the size of the output.
+    ///
+    /// See [`Filter::token_merge()`].
+    pub merge_tokens: bool,
+    /// Replace the wrapping `<div>` element with another element,
+    /// e.g. `Some("figure".into())` outputs
+    /// `<figure class="highlight">...</figure>`.
+    pub root_element: Option<String>,
+    /// Emphasize specific lines, see [`HlLines`]. If empty (the default),
+    /// then the `hl_lines` option is not passed to `pygmentize` at all.
+    ///
+    /// _Pygments' LaTeX and SVG formatters do not support
+    /// emphasizing lines, so only this formatter has the option._
+    ///
+    /// ```rust
+    /// use pygmentize::{hl_lines, HtmlFormatter, PygmentizeFormatter};
+    ///
+    /// let fmt = HtmlFormatter {
+    ///     highlight_lines: hl_lines![1, 3..=4],
+    ///     ..HtmlFormatter::default()
+    /// };
+    /// assert_eq!(fmt.options_str().as_deref(), Some("hl_lines=1 3 4"));
+    ///
+    /// let fmt = HtmlFormatter {
+    ///     highlight_lines: vec![2, 5].into(),
+    ///     ..HtmlFormatter::default()
+    /// };
+    /// assert_eq!(fmt.options_str().as_deref(), Some("hl_lines=2 5"));
+    ///
+    /// assert_eq!(HtmlFormatter::default().options_str(), None);
+    /// ```
+    pub highlight_lines: HlLines,
+    /// Remove trailing whitespace at the end of each line, which is
+    /// outside of tokens or in whitespace tokens. Indentation,
+    /// whitespace between tokens, and whitespace within e.g.
+    /// comments and strings is preserved.
+    pub trim_line_trailing_whitespace: bool,
+    /// Prepend a `<style>` block with the style's rules, scoped to a
+    /// class unique to the output, which is added to the wrapper, i.e.
+    /// each output is styled independently without a shared stylesheet.
+    /// Ignored if [`inline_styles`](Self::inline_styles) is set, which
+    /// outputs `style` attributes on each token instead.
+    ///
+    /// _This requires an additional `pygmentize -S` call._
+    ///
+    /// ```rust
+    /// use pygmentize::{HtmlFormatter, PygmentizeError};
+    ///
+    /// # fn main() -> Result<(), PygmentizeError> {
+    /// let fmt = HtmlFormatter {
+    ///     scoped_style: true,
+    ///     ..HtmlFormatter::default()
+    /// };
+    ///
+    /// let a = pygmentize::highlight("let a = 1;", Some("rust"), &fmt)?;
+    /// let b = pygmentize::highlight("let b = 2;", Some("rust"), &fmt)?;
+    /// assert!(a.starts_with("<style>"));
+    ///
+    /// let class = |html: &str| {
+    ///     let start = html.find(r#"<div class="highlight "#).unwrap();
+    ///     html[start..].split('"').nth(1).unwrap().to_owned()
+    /// };
+    /// assert_ne!(class(&a), class(&b));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub scoped_style: bool,
+    /// Add accessibility attributes, i.e. `role="code"` on the wrapper,
+    /// and `aria-hidden="true"` on the line numbers, such that screen
+    /// readers do not read the line numbers as part of the code.
+    ///
+    /// ```rust
+    /// use pygmentize::{HtmlFormatter, PygmentizeError};
+    ///
+    /// # fn main() -> Result<(), PygmentizeError> {
+    /// let fmt = HtmlFormatter {
+    ///     line_numbers: true,
+    ///     accessibility: true,
+    ///     ..HtmlFormatter::default()
+    /// };
+    ///
+    /// let html = pygmentize::highlight("let x = 1;", Some("rust"), &fmt)?;
+    /// assert!(html.starts_with(r#"<div role="code" class="highlight">"#));
+    /// assert!(html.contains(r#"<td aria-hidden="true" class="linenos">"#));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub accessibility: bool,
+    /// Output HTML5 instead of HTML 4. Pygments' output is mostly valid
+    /// HTML5 already, i.e. tags and attributes are lowercase, and no
+    /// elements are self-closing. Enabling this additionally:
+    ///
+    /// - Wraps the code in a `<code>` element within the `<pre>`
+    ///   element, i.e. `<pre><code>...</code></pre>`, as recommended
+    ///   by the HTML5 specification (Pygments' `wrapcode` option).
+    /// - For full documents, replaces the HTML 4.01 doctype with
+    ///   `<!DOCTYPE html>`, replaces
+    ///   `<meta http-equiv="content-type" content="text/html; charset=utf-8">`
+    ///   with `<meta charset="utf-8">`, and removes the obsolete
+    ///   `type="text/css"` attribute from `<style>`.
+    ///
+    /// ```rust
+    /// use pygmentize::{HtmlFormatter, PygmentizeError};
+    ///
+    /// # fn main() -> Result<(), PygmentizeError> {
+    /// let fmt = HtmlFormatter {
+    ///     html5: true,
+    ///     ..HtmlFormatter::default()
+    /// };
+    ///
+    /// let html = pygmentize::highlight("let x = 1;", Some("rust"), &fmt)?;
+    /// assert!(html.contains("<pre><span></span><code>"));
+    /// assert!(html.contains("</code></pre>"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub html5: bool,
+    /// Wrap regions, i.e. lines between region markers such as
+    /// `// region` and `// endregion`, in collapsible `<details>`
+    /// elements, where the start marker line is the `<summary>`.
+    /// Regions can be nested, and unmatched markers are ignored.
+    ///
+    /// The markers are comments containing `region` or `endregion`,
+    /// optionally prefixed with `#`, e.g. `// region Setup`,
+    /// `// #region`, `# region`, `#region` (C#), `#pragma region` (C++),
+    /// `<!-- #region -->` (HTML), and `-- region` (SQL, Lua).
+    ///
+    /// _This has no effect if `line_numbers` or `copyable` is enabled,
+    /// as the line numbers are in a separate table cell, and would not
+    /// be collapsed along with the code._
+    ///
+    /// ```rust
+    /// use pygmentize::{HtmlFormatter, PygmentizeError};
+    ///
+    /// # fn main() -> Result<(), PygmentizeError> {
+    /// let fmt = HtmlFormatter {
+    ///     fold_regions: true,
+    ///     ..HtmlFormatter::default()
+    /// };
+    ///
+    /// let code = "// region Setup\nlet x = 1;\n// endregion\nlet y = 2;";
+    /// let html = pygmentize::highlight(code, Some("rust"), &fmt)?;
+    /// assert!(html.contains("<details><summary>"));
+    /// assert!(html.contains("endregion</span></details>"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fold_regions: bool,
+    /// Replace tabs in the code with the given number of spaces, such
+    /// that the indentation does not depend on the browser's `tab-size`.
+    /// Tabs within tags, e.g. in attributes, are left as is.
+    ///
+    /// ```rust
+    /// use pygmentize::{HtmlFormatter, PygmentizeError};
+    ///
+    /// # fn main() -> Result<(), PygmentizeError> {
+    /// let fmt = HtmlFormatter {
+    ///     expand_tabs: Some(4),
+    ///     ..HtmlFormatter::default()
+    /// };
+    ///
+    /// let html = pygmentize::highlight("fn main() {\n\tlet x = 1;\n}", Some("rust"), &fmt)?;
+    /// assert!(!html.contains('\t'));
+    /// assert!(html.contains(r#"<span class="w">    </span>"#));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub expand_tabs: Option<usize>,
+    /// Highlight code tags in comments, i.e. `XXX`, `TODO`, `FIXME`,
+    /// `BUG`, and `NOTE`, or `custom_code_tags` if not empty.
+    ///
+    /// The tags are output as `Comment.Special` tokens, i.e. whether they
+    /// stand out depends on the style, see [`Filter::code_tags()`].
+    ///
+    /// ```rust
+    /// use pygmentize::{HtmlFormatter, PygmentizeError};
+    ///
+    /// # fn main() -> Result<(), PygmentizeError> {
+    /// let fmt = HtmlFormatter {
+    ///     code_tags: true,
+    ///     ..HtmlFormatter::default()
+    /// };
+    ///
+    /// let html = pygmentize::highlight("// TODO: Implement", Some("rust"), &fmt)?;
+    /// assert!(html.contains(r#"<span class="cs">TODO</span>"#));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub code_tags: bool,
+    /// Code tags to highlight instead of the default tags, e.g.
+    /// `vec!["TODO".into(), "HACK".into()]`.
+    /// Only has an effect if `code_tags` is enabled.
+    pub custom_code_tags: Vec<String>,
+    /// The style, i.e. color scheme, e.g. `"monokai"`. If `None`,
+    /// Pygments uses the `"default"` style.
+    ///
+    /// See <https://pygments.org/styles/> for available styles.
+    pub style: Option<String>,
+    /// Output a complete standalone HTML document, including a
+    /// `<style>` block with the rules of the style, i.e. the output
+    /// can be viewed without supplying an external stylesheet.
+    ///
+    /// ```rust
+    /// use pygmentize::{HtmlFormatter, PygmentizeError};
+    ///
+    /// # fn main() -> Result<(), PygmentizeError> {
+    /// let fmt = HtmlFormatter {
+    ///     full: true,
+    ///     title: Some(String::from("Hello, <World>")),
+    ///     ..HtmlFormatter::default()
+    /// };
+    ///
+    /// let html = pygmentize::highlight("fn main() {}", Some("rust"), &fmt)?;
+    /// assert!(html.starts_with("<!DOCTYPE html"));
+    /// assert!(html.contains("<title>Hello&#44; &lt;World&gt;</title>"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub full: bool,
+    /// The title of the document, which is HTML-escaped.
+    /// Only has an effect if `full` is enabled.
+    pub title: Option<String>,
+    /// Append a copy of the source, e.g. for a "copy raw" button, in a
+    /// `<script type="text/plain" class="raw-source">` element after the
+    /// highlighted code, which is not rendered. The source is the input
+    /// code as is, e.g. leading newlines and tabs are kept, regardless of
+    /// [`LexerOptions`](crate::LexerOptions). It is HTML-escaped, such that
+    /// it cannot break out of the element, i.e. it must be unescaped
+    /// client-side, e.g.:
+    ///
+    /// ```js
+    /// const escaped = block.nextElementSibling.textContent;
+    /// const source = new DOMParser().parseFromString(escaped, "text/html").documentElement.textContent;
+    /// ```
+    ///
+    /// ```rust
+    /// use pygmentize::{HtmlFormatter, PygmentizeError};
+    ///
+    /// # fn main() -> Result<(), PygmentizeError> {
+    /// let fmt = HtmlFormatter {
+    ///     embed_source: true,
+    ///     ..HtmlFormatter::default()
+    /// };
+    ///
+    /// let html = pygmentize::highlight("\n\tlet s = \"</script>\";", Some("rust"), &fmt)?;
+    /// assert!(html.ends_with(
+    ///     "<script type=\"text/plain\" class=\"raw-source\">\n\tlet s = &quot;&lt;/script&gt;&quot;;</script>\n"
+    /// ));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub embed_source: bool,
+    /// Additional options passed to `pygmentize`, e.g. options which are
+    /// not covered by the other fields. These take precedence over the
+    /// options set by the other fields.
+    ///
+    /// Neither keys nor values can contain `,`, as `pygmentize`
+    /// separates options by `,`, otherwise
+    /// [`PygmentizeError::InvalidOption`] is returned.
+    ///
+    /// See <https://pygments.org/docs/formatters/#HtmlFormatter>
+    /// for available options.
+    ///
+    /// ```rust
+    /// use pygmentize::{HtmlFormatter, PygmentizeError};
+    ///
+    /// # fn main() -> Result<(), PygmentizeError> {
+    /// let mut fmt = HtmlFormatter::default();
+    /// fmt.options.insert("cssclass".into(), "code".into());
+    ///
+    /// let html = pygmentize::highlight("fn main() {}", Some("rust"), &fmt)?;
+    /// assert!(html.starts_with(r#"<div class="code">"#));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub options: BTreeMap<String, String>,
+    /// Match the HTML of code cells exported by Jupyter's nbconvert,
+    /// such that the output renders like native cells in e.g. JupyterLab,
+    /// given the language of the notebook, e.g. `Some("ipython3".into())`.
+    ///
+    /// Adds the `hl-{language}` class to the wrapper, i.e. the output is
+    /// wrapped in `<div class="highlight hl-ipython3">`, which nbconvert's
+    /// and JupyterLab's stylesheets target.
+    ///
+    /// This is an `Option<String>` instead of a `bool`, as the class is
+    /// the language of the notebook, e.g. `ipython3`, which is not
+    /// necessarily the `lang` passed to [`highlight()`](crate::highlight),
+    /// e.g. `None` when guessing, or `"python"`.
+    ///
+    /// No `highlight-ipynb` class is added, as nbconvert's HTML exporter
+    /// does not add one to code cells, i.e. the `hl-{language}` class is
+    /// the only difference to the default wrapper.
+    ///
+    /// ```rust
+    /// use pygmentize::{HtmlFormatter, PygmentizeError};
+    ///
+    /// # fn main() -> Result<(), PygmentizeError> {
+    /// let fmt = HtmlFormatter {
+    ///     jupyter: Some(String::from("ipython3")),
+    ///     ..HtmlFormatter::default()
+    /// };
+    ///
+    /// let html = pygmentize::highlight("print('Hello World')", Some("ipython3"), &fmt)?;
+    /// assert!(html.starts_with(r#"<div class="highlight hl-ipython3"><pre>"#));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub jupyter: Option<String>,
+    /// The class of the wrapper, i.e. `<div class="{css_class}">`,
+    /// instead of `highlight`. With [`line_numbers`](Self::line_numbers),
+    /// the table class is `{css_class}table` accordingly.
+    ///
+    /// Cannot contain `,`, otherwise [`PygmentizeError::InvalidOption`]
+    /// is returned.
+    ///
+    /// ```rust
+    /// use pygmentize::{HtmlFormatter, PygmentizeError, PygmentizeFormatter};
+    ///
+    /// # fn main() -> Result<(), PygmentizeError> {
+    /// let fmt = HtmlFormatter {
+    ///     css_class: Some(String::from("code")),
+    ///     line_numbers: true,
+    ///     ..HtmlFormatter::default()
+    /// };
+    /// assert_eq!(fmt.options_str().as_deref(), Some("cssclass=code,linenos=true"));
+    ///
+    /// let html = pygmentize::highlight("fn main() {}", Some("rust"), &fmt)?;
+    /// assert!(html.starts_with(r#"<div class="code"><table class="codetable">"#));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub css_class: Option<String>,
+    /// Output the style as `style` attributes on each token, instead of
+    /// classes, i.e. the output can be rendered without any stylesheet,
+    /// e.g. in emails. Unlike [`scoped_style`](Self::scoped_style),
+    /// which is ignored if this is set, no `<style>` block is needed.
+    ///
+    /// ```rust
+    /// use pygmentize::{HtmlFormatter, PygmentizeError};
+    ///
+    /// # fn main() -> Result<(), PygmentizeError> {
+    /// let fmt = HtmlFormatter {
+    ///     inline_styles: true,
+    ///     full: true,
+    ///     style: Some(String::from("monokai")),
+    ///     ..HtmlFormatter::default()
+    /// };
+    ///
+    /// let html = pygmentize::highlight("fn main() {}", Some("rust"), &fmt)?;
+    /// assert!(html.contains(r#"<span style="color: #66D9EF">fn</span>"#));
+    /// assert!(!html.contains("<span class="));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub inline_styles: bool,
+    /// How line numbers are output, if `line_numbers` is enabled.
+    ///
+    /// [`LineNumbers::CssCounter`] cannot be combined with `copyable`,
+    /// otherwise [`PygmentizeError::InvalidOption`] is returned.
+    ///
+    /// ```rust
+    /// use pygmentize::{HtmlFormatter, LineNumbers, PygmentizeError};
+    ///
+    /// # fn main() -> Result<(), PygmentizeError> {
+    /// let fmt = HtmlFormatter {
+    ///     line_numbers: true,
+    ///     line_number_mode: LineNumbers::CssCounter,
+    ///     line_number_start: Some(10),
+    ///     ..HtmlFormatter::default()
+    /// };
+    ///
+    /// let highlighted = pygmentize::highlight_html_with_css("let x = 1;\nlet y = 2;", Some("rust"), &fmt)?;
+    /// assert!(highlighted.html.contains(r#"<pre style="counter-reset: line 9">"#));
+    /// assert_eq!(highlighted.html.matches(r#"<span class="line">"#).count(), 2);
+    /// assert!(!highlighted.html.contains("id="));
+    /// assert!(highlighted.css.contains("counter-increment: line;"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub line_number_mode: LineNumbers,
 }
 
 impl Default for HtmlFormatter {
     fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HtmlFormatter {
+    pub const fn new() -> Self {
         Self {
             line_numbers: false,
-            // class_prefix: None,
+            line_number_start: None,
+            version_comment: false,
+            reject_highlighted: false,
+            copyable: false,
+            merge_tokens: false,
+            root_element: None,
+            highlight_lines: HlLines::new(),
+            trim_line_trailing_whitespace: false,
+            scoped_style: false,
+            accessibility: false,
+            html5: false,
+            fold_regions: false,
+            expand_tabs: None,
+            code_tags: false,
+            custom_code_tags: Vec::new(),
+            style: None,
+            full: false,
+            title: None,
+            embed_source: false,
+            options: BTreeMap::new(),
+            jupyter: None,
+            css_class: None,
+            inline_styles: false,
+            line_number_mode: LineNumbers::Inline,
         }
     }
-}
 
-impl HtmlFormatter {
-    pub fn new() -> Self {
-        Self::default()
+    /// Returns `true` if line numbers are output using CSS counters,
+    /// see [`LineNumbers::CssCounter`].
+    pub(crate) fn css_line_numbers(&self) -> bool {
+        self.line_numbers && !self.copyable && self.line_number_mode == LineNumbers::CssCounter
     }
 }
 
@@ -56,11 +637,167 @@ impl PygmentizeFormatter for HtmlFormatter {
     const SHORT_NAME: &'static str = "html";
 
     fn options_str(&self) -> Option<Cow<'_, str>> {
-        if self.line_numbers {
-            Some(Cow::Borrowed("linenos=true"))
+        let mut opts = Options::new();
+
+        if self.copyable {
+            opts.push("linenos", "table");
+        } else if self.line_numbers {
+            match self.line_number_mode {
+                LineNumbers::Inline => opts.push("linenos", "true"),
+                // The ids are replaced with a class by `post_process()`
+                LineNumbers::CssCounter => opts.push("linespans", "line"),
+            }
+        }
+
+        if self.copyable || self.line_numbers {
+            if let Some(start) = self.line_number_start {
+                opts.push("linenostart", start);
+            }
+        }
+
+        if !self.highlight_lines.is_empty() {
+            opts.push("hl_lines", &self.highlight_lines);
+        }
+
+        if self.html5 {
+            opts.push("wrapcode", "true");
+        }
+
+        if let Some(style) = &self.style {
+            opts.push("style", style);
+        }
+
+        if let Some(css_class) = &self.css_class {
+            opts.push("cssclass", css_class);
+        }
+
+        if self.inline_styles {
+            opts.push("noclasses", "true");
+        }
+
+        if self.full {
+            opts.push("full", "true");
+
+            if let Some(title) = &self.title {
+                opts.push("title", escape_title(title));
+            }
+        }
+
+        opts.extend(&self.options);
+
+        opts.finish()
+    }
+
+    fn validate(&self) -> Result<(), PygmentizeError> {
+        if self.copyable && self.line_number_mode == LineNumbers::CssCounter {
+            return Err(PygmentizeError::InvalidOption(
+                "line_number_mode",
+                "`LineNumbers::CssCounter` cannot be combined with `copyable`".to_owned(),
+            ));
+        }
+        validate_line_numbers(self.line_number_start, None)?;
+        validate_no_comma("css_class", self.css_class.as_deref())?;
+        validate_options(&self.options)
+    }
+
+    fn style(&self) -> Option<&str> {
+        self.style.as_deref()
+    }
+
+    fn filters(&self) -> Cow<'_, [Filter]> {
+        let mut filters = Vec::new();
+
+        if self.code_tags {
+            filters.push(code_tags_filter(&self.custom_code_tags));
+        }
+
+        // Merge last, such that the tokens output by other filters are merged
+        if self.merge_tokens {
+            filters.push(Filter::token_merge());
+        }
+
+        if filters.is_empty() {
+            Cow::Borrowed(&[])
         } else {
-            None
+            Cow::Owned(filters)
+        }
+    }
+
+    fn validate_input(&self, code: &str) -> Result<(), PygmentizeError> {
+        if self.reject_highlighted && is_highlighted(code) {
+            return Err(PygmentizeError::AlreadyHighlighted);
+        }
+        Ok(())
+    }
+
+    fn post_process(&self, code: &str, output: String) -> Result<String, PygmentizeError> {
+        self.post_process_with(None, code, output)
+    }
+
+    fn post_process_with(
+        &self,
+        bin: Option<&OsStr>,
+        code: &str,
+        mut output: String,
+    ) -> Result<String, PygmentizeError> {
+        if self.copyable {
+            output = output.replacen(r#"<td class="code">"#, r#"<td class="code" data-code>"#, 1);
+        }
+
+        if let Some(tab_size) = self.expand_tabs {
+            output = expand_tabs(&output, tab_size);
+        }
+
+        if self.trim_line_trailing_whitespace {
+            output = trim_line_trailing_whitespace(&output);
+        }
+
+        if self.fold_regions && !self.line_numbers && !self.copyable {
+            output = fold_regions(&output);
+        }
+
+        if self.css_line_numbers() {
+            output = line_spans_to_class(&output);
+            if let Some(start) = self.line_number_start {
+                output = set_line_counter_start(&output, start);
+            }
+        }
+
+        if self.scoped_style && !self.inline_styles {
+            output = add_scoped_style(
+                bin,
+                &output,
+                self.style().unwrap_or(DEFAULT_STYLE),
+                self.css_line_numbers(),
+            )?;
+        }
+
+        if let Some(language) = &self.jupyter {
+            output = add_jupyter_class(&output, language);
+        }
+
+        if self.accessibility {
+            output = add_accessibility_attributes(&output);
+        }
+
+        if self.html5 {
+            output = to_html5(&output);
+        }
+
+        if let Some(tag) = &self.root_element {
+            output = replace_root_element(&output, tag);
         }
+
+        if self.embed_source {
+            output = embed_source(&output, code);
+        }
+
+        if self.version_comment {
+            let version = version_with(bin)?;
+            output = format!("<!-- pygments {version} -->\n{output}");
+        }
+
+        Ok(output)
     }
 }
 
@@ -71,23 +808,77 @@ impl PygmentizeFormatter for HtmlFormatter {
 ///
 /// See <https://pygments.org/docs/formatters/#SvgFormatter>
 /// for more information.
-#[derive(Clone, Debug)]
+#[derive(Clone, Hash, Debug)]
 pub struct SvgFormatter {
     /// Output line numbers.
+    ///
+    /// The line numbers are rendered in the gutter, using the
+    /// color of comments of the style.
     pub line_numbers: bool,
+    /// The line number of the first line.
+    /// Only has an effect if `line_numbers` is enabled.
+    pub line_number_start: Option<usize>,
+    /// Only print every `n`th line number.
+    /// Only has an effect if `line_numbers` is enabled.
+    pub line_number_step: Option<usize>,
+    /// The width of the line number gutter. Pygments defaults to 3 times
+    /// the line height, which fits line numbers of up to 4 digits.
+    /// Only has an effect if `line_numbers` is enabled.
+    pub line_number_width: Option<usize>,
+    /// The style, i.e. color scheme, see [`HtmlFormatter::style`].
+    pub style: Option<String>,
+    /// The font family of the `<text>` elements, e.g. `"Fira Code"`
+    /// or a list of fonts, e.g. `"'Fira Code', monospace"`. If `None`,
+    /// Pygments uses `"monospace"`.
+    ///
+    /// It is passed verbatim, i.e. it can contain `,`, but Pygments
+    /// does not escape it, so font names must be quoted using `'`.
+    ///
+    /// ```rust
+    /// use pygmentize::SvgFormatter;
+    ///
+    /// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+    /// let fmt = SvgFormatter {
+    ///     font_family: Some(String::from("'Fira Code', monospace")),
+    ///     font_size: Some(String::from("12px")),
+    ///     ..SvgFormatter::default()
+    /// };
+    ///
+    /// let svg = pygmentize::highlight("fn main() {}", Some("rust"), &fmt)?;
+    /// assert!(svg.contains(r#"font-family="'Fira Code', monospace" font-size="12px""#));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub font_family: Option<String>,
+    /// The font size of the `<text>` elements, e.g. `"12px"`.
+    /// If `None`, Pygments uses `"14px"`.
+    ///
+    /// Cannot contain `,`, otherwise [`PygmentizeError::InvalidOption`]
+    /// is returned.
+    pub font_size: Option<String>,
+    /// Additional options passed to `pygmentize`, see
+    /// [`HtmlFormatter::options`].
+    pub options: BTreeMap<String, String>,
 }
 
 impl Default for SvgFormatter {
     fn default() -> Self {
-        Self {
-            line_numbers: false,
-        }
+        Self::new()
     }
 }
 
 impl SvgFormatter {
-    pub fn new() -> Self {
-        Self::default()
+    pub const fn new() -> Self {
+        Self {
+            line_numbers: false,
+            line_number_start: None,
+            line_number_step: None,
+            line_number_width: None,
+            style: None,
+            font_family: None,
+            font_size: None,
+            options: BTreeMap::new(),
+        }
     }
 }
 
@@ -95,11 +886,52 @@ impl PygmentizeFormatter for SvgFormatter {
     const SHORT_NAME: &'static str = "svg";
 
     fn options_str(&self) -> Option<Cow<'_, str>> {
+        let mut opts = Options::new();
+
         if self.line_numbers {
-            Some(Cow::Borrowed("linenos=true"))
-        } else {
-            None
+            opts.push("linenos", "true");
+
+            if let Some(start) = self.line_number_start {
+                opts.push("linenostart", start);
+            }
+
+            if let Some(step) = self.line_number_step {
+                opts.push("linenostep", step);
+            }
+
+            if let Some(width) = self.line_number_width {
+                opts.push("linenowidth", width);
+            }
+        }
+
+        if let Some(style) = &self.style {
+            opts.push("style", style);
         }
+
+        if let Some(font_size) = &self.font_size {
+            opts.push("fontsize", font_size);
+        }
+
+        opts.extend(&self.options);
+
+        opts.finish()
+    }
+
+    fn style(&self) -> Option<&str> {
+        self.style.as_deref()
+    }
+
+    fn verbatim_options(&self) -> Vec<(&'static str, &str)> {
+        match &self.font_family {
+            Some(font_family) => vec![("fontfamily", font_family)],
+            None => Vec::new(),
+        }
+    }
+
+    fn validate(&self) -> Result<(), PygmentizeError> {
+        validate_line_numbers(self.line_number_start, self.line_number_step)?;
+        validate_no_comma("font_size", self.font_size.as_deref())?;
+        validate_options(&self.options)
     }
 }
 
@@ -108,23 +940,149 @@ impl PygmentizeFormatter for SvgFormatter {
 ///
 /// See <https://pygments.org/docs/formatters/#LatexFormatter>
 /// for more information.
-#[derive(Clone, Debug)]
+#[derive(Clone, Hash, Debug)]
 pub struct LatexFormatter {
     /// Output line numbers.
     pub line_numbers: bool,
+    /// The line number of the first line, which is emitted as the
+    /// `firstnumber=` option of the `Verbatim` environment.
+    /// Only has an effect if `line_numbers` is enabled.
+    ///
+    /// ```rust
+    /// use pygmentize::{LatexFormatter, PygmentizeError, PygmentizeFormatter};
+    ///
+    /// # fn main() -> Result<(), PygmentizeError> {
+    /// let fmt = LatexFormatter {
+    ///     line_numbers: true,
+    ///     line_number_start: Some(10),
+    ///     ..LatexFormatter::default()
+    /// };
+    /// assert_eq!(fmt.options_str().as_deref(), Some("linenos=true,linenostart=10"));
+    ///
+    /// let latex = pygmentize::highlight("let x = 1;", Some("rust"), &fmt)?;
+    /// assert!(latex.contains("numbers=left,firstnumber=10"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub line_number_start: Option<usize>,
+    /// Only print every `n`th line number, which is emitted as the
+    /// `stepnumber=` option of the `Verbatim` environment.
+    /// Only has an effect if `line_numbers` is enabled.
+    ///
+    /// Both `line_number_start` and `line_number_step` must be at least
+    /// `1`, otherwise [`PygmentizeError::InvalidOption`] is returned.
+    ///
+    /// ```rust
+    /// use pygmentize::{LatexFormatter, PygmentizeError};
+    ///
+    /// let fmt = LatexFormatter {
+    ///     line_numbers: true,
+    ///     line_number_step: Some(0),
+    ///     ..LatexFormatter::default()
+    /// };
+    ///
+    /// let res = pygmentize::highlight("let x = 1;", Some("rust"), &fmt);
+    /// assert!(matches!(res, Err(PygmentizeError::InvalidOption("line_number_step", _))));
+    /// ```
+    pub line_number_step: Option<usize>,
+    /// The style, i.e. color scheme, see [`HtmlFormatter::style`].
+    pub style: Option<String>,
+    /// Output a complete, standalone LaTeX document, including
+    /// the style definitions, instead of only the `Verbatim`
+    /// environment.
+    ///
+    /// ```rust
+    /// use pygmentize::{LatexFormatter, PygmentizeError};
+    ///
+    /// # fn main() -> Result<(), PygmentizeError> {
+    /// let fmt = LatexFormatter {
+    ///     full: true,
+    ///     preamble: Some(String::from(r"\usepackage[scaled=0.9,T1]{lmodern}")),
+    ///     command_prefix: Some(String::from("PYa")),
+    ///     ..LatexFormatter::default()
+    /// };
+    ///
+    /// let latex = pygmentize::highlight("x = 1", Some("python"), &fmt)?;
+    /// assert!(latex.contains(r"\documentclass{article}"));
+    /// assert!(latex.contains("\n\\usepackage[scaled=0.9,T1]{lmodern}\n"));
+    /// assert!(latex.contains(r"\PYa{n}{x}"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub full: bool,
+    /// Additional LaTeX inserted into the preamble of the document,
+    /// e.g. `\usepackage[T1]{fontenc}`. Only has an effect if `full`
+    /// is enabled. It is passed verbatim, i.e. it can contain `,`.
+    pub preamble: Option<String>,
+    /// The prefix of the LaTeX commands, instead of `PY`, e.g. such that
+    /// blocks highlighted with different styles can be used within the
+    /// same document, without their commands colliding.
+    ///
+    /// As it is part of command names, it must consist of ASCII letters
+    /// only, otherwise [`PygmentizeError::InvalidOption`] is returned.
+    pub command_prefix: Option<String>,
+    /// Render the contents of comments as LaTeX, instead of escaping them,
+    /// e.g. such that comments can contain math.
+    pub tex_comments: bool,
+    /// Render LaTeX math mode within comments, i.e. text between `$`,
+    /// instead of escaping it, unlike `tex_comments` leaving the
+    /// rest of the comment escaped.
+    ///
+    /// ```rust
+    /// use pygmentize::{LatexFormatter, PygmentizeError, PygmentizeFormatter};
+    ///
+    /// # fn main() -> Result<(), PygmentizeError> {
+    /// let expected = [
+    ///     (false, false, "linenos=true"),
+    ///     (true, false, "linenos=true,texcomments=true"),
+    ///     (false, true, "linenos=true,mathescape=true"),
+    ///     (true, true, "linenos=true,mathescape=true,texcomments=true"),
+    /// ];
+    /// for (tex_comments, math_escape, options) in expected {
+    ///     let fmt = LatexFormatter {
+    ///         line_numbers: true,
+    ///         tex_comments,
+    ///         math_escape,
+    ///         ..LatexFormatter::default()
+    ///     };
+    ///     assert_eq!(fmt.options_str().as_deref(), Some(options));
+    /// }
+    ///
+    /// let fmt = LatexFormatter {
+    ///     math_escape: true,
+    ///     ..LatexFormatter::default()
+    /// };
+    /// let latex = pygmentize::highlight("x = 1 # $a^2$", Some("python"), &fmt)?;
+    /// assert!(latex.contains(r"\PY{c+c1}{\PYZsh{} $a^2$}"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub math_escape: bool,
+    /// Additional options passed to `pygmentize`, see
+    /// [`HtmlFormatter::options`].
+    pub options: BTreeMap<String, String>,
 }
 
 impl Default for LatexFormatter {
     fn default() -> Self {
-        Self {
-            line_numbers: false,
-        }
+        Self::new()
     }
 }
 
 impl LatexFormatter {
-    pub fn new() -> Self {
-        Self::default()
+    pub const fn new() -> Self {
+        Self {
+            line_numbers: false,
+            line_number_start: None,
+            line_number_step: None,
+            style: None,
+            full: false,
+            preamble: None,
+            command_prefix: None,
+            tex_comments: false,
+            math_escape: false,
+            options: BTreeMap::new(),
+        }
     }
 }
 
@@ -132,12 +1090,68 @@ impl PygmentizeFormatter for LatexFormatter {
     const SHORT_NAME: &'static str = "latex";
 
     fn options_str(&self) -> Option<Cow<'_, str>> {
+        let mut opts = Options::new();
+
         if self.line_numbers {
-            Some(Cow::Borrowed("linenos=true"))
-        } else {
-            None
+            opts.push("linenos", "true");
+
+            if let Some(start) = self.line_number_start {
+                opts.push("linenostart", start);
+            }
+
+            if let Some(step) = self.line_number_step {
+                opts.push("linenostep", step);
+            }
+        }
+
+        if let Some(style) = &self.style {
+            opts.push("style", style);
+        }
+
+        if self.full {
+            opts.push("full", "true");
+        }
+
+        if let Some(prefix) = &self.command_prefix {
+            opts.push("commandprefix", prefix);
+        }
+
+        if self.tex_comments {
+            opts.push("texcomments", "true");
+        }
+
+        if self.math_escape {
+            opts.push("mathescape", "true");
+        }
+
+        opts.extend(&self.options);
+
+        opts.finish()
+    }
+
+    fn style(&self) -> Option<&str> {
+        self.style.as_deref()
+    }
+
+    fn verbatim_options(&self) -> Vec<(&'static str, &str)> {
+        match &self.preamble {
+            Some(preamble) if self.full => vec![("preamble", preamble)],
+            _ => Vec::new(),
         }
     }
+
+    fn validate(&self) -> Result<(), PygmentizeError> {
+        validate_line_numbers(self.line_number_start, self.line_number_step)?;
+        if let Some(prefix) = &self.command_prefix {
+            if prefix.is_empty() || !prefix.bytes().all(|b| b.is_ascii_alphabetic()) {
+                return Err(PygmentizeError::InvalidOption(
+                    "command_prefix",
+                    format!("`{prefix}` must only contain ASCII letters"),
+                ));
+            }
+        }
+        validate_options(&self.options)
+    }
 }
 
 /// Format tokens with ANSI color sequences, for output in a text
@@ -146,23 +1160,70 @@ impl PygmentizeFormatter for LatexFormatter {
 ///
 /// See <https://pygments.org/docs/formatters/#TerminalFormatter>
 /// for more information.
-#[derive(Clone, Debug)]
+#[derive(Clone, Hash, Debug)]
 pub struct TerminalFormatter {
     /// Output line numbers.
     pub line_numbers: bool,
+    /// Highlight code tags in comments, see [`HtmlFormatter::code_tags`].
+    pub code_tags: bool,
+    /// Code tags to highlight instead of the default tags,
+    /// see [`HtmlFormatter::custom_code_tags`].
+    pub custom_code_tags: Vec<String>,
+    /// The background of the terminal, which the colors are chosen
+    /// for. The default is [`Background::Light`], like Pygments.
+    ///
+    /// ```rust
+    /// use pygmentize::{Background, PygmentizeFormatter, TerminalFormatter};
+    ///
+    /// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+    /// let fmt = TerminalFormatter {
+    ///     background: Background::Dark,
+    ///     ..TerminalFormatter::default()
+    /// };
+    /// assert_eq!(fmt.options_str().as_deref(), Some("bg=dark"));
+    ///
+    /// let output = fmt.highlight("fn main() {}", Some("rust"))?;
+    /// assert!(output.starts_with("\x1b[94mfn"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub background: Background,
+    /// The style, i.e. color scheme, see [`HtmlFormatter::style`].
+    pub style: Option<String>,
+    /// Additional options passed to `pygmentize`, see
+    /// [`HtmlFormatter::options`].
+    pub options: BTreeMap<String, String>,
 }
 
 impl Default for TerminalFormatter {
     fn default() -> Self {
-        Self {
-            line_numbers: false,
-        }
+        Self::new()
     }
 }
 
 impl TerminalFormatter {
-    pub fn new() -> Self {
-        Self::default()
+    /// Same as [`TerminalFormatter::default()`], but usable in `const` contexts.
+    ///
+    /// ```rust
+    /// use pygmentize::{PygmentizeFormatter, TerminalFormatter};
+    ///
+    /// const FMT: TerminalFormatter = TerminalFormatter::new();
+    ///
+    /// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+    /// let output = FMT.highlight("fn main() {}", Some("rust"))?;
+    /// println!("{output}");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub const fn new() -> Self {
+        Self {
+            line_numbers: false,
+            code_tags: false,
+            custom_code_tags: Vec::new(),
+            background: Background::Light,
+            style: None,
+            options: BTreeMap::new(),
+        }
     }
 }
 
@@ -170,11 +1231,35 @@ impl PygmentizeFormatter for TerminalFormatter {
     const SHORT_NAME: &'static str = "terminal";
 
     fn options_str(&self) -> Option<Cow<'_, str>> {
+        let mut opts = Options::new();
+
         if self.line_numbers {
-            Some(Cow::Borrowed("linenos=true"))
-        } else {
-            None
+            opts.push("linenos", "true");
+        }
+
+        if self.background != Background::Light {
+            opts.push("bg", self.background);
+        }
+
+        if let Some(style) = &self.style {
+            opts.push("style", style);
         }
+
+        opts.extend(&self.options);
+
+        opts.finish()
+    }
+
+    fn validate(&self) -> Result<(), PygmentizeError> {
+        validate_options(&self.options)
+    }
+
+    fn style(&self) -> Option<&str> {
+        self.style.as_deref()
+    }
+
+    fn filters(&self) -> Cow<'_, [Filter]> {
+        code_tags_filters(self.code_tags, &self.custom_code_tags)
     }
 }
 
@@ -184,23 +1269,37 @@ impl PygmentizeFormatter for TerminalFormatter {
 ///
 /// See <https://pygments.org/docs/formatters/#TerminalTrueColorFormatter>
 /// for more information.
-#[derive(Clone, Debug)]
+#[derive(Clone, Hash, Debug)]
 pub struct TerminalTrueColorFormatter {
     /// Output line numbers.
     pub line_numbers: bool,
+    /// Highlight code tags in comments, see [`HtmlFormatter::code_tags`].
+    pub code_tags: bool,
+    /// Code tags to highlight instead of the default tags,
+    /// see [`HtmlFormatter::custom_code_tags`].
+    pub custom_code_tags: Vec<String>,
+    /// The style, i.e. color scheme, see [`HtmlFormatter::style`].
+    pub style: Option<String>,
+    /// Additional options passed to `pygmentize`, see
+    /// [`HtmlFormatter::options`].
+    pub options: BTreeMap<String, String>,
 }
 
 impl Default for TerminalTrueColorFormatter {
     fn default() -> Self {
-        Self {
-            line_numbers: false,
-        }
+        Self::new()
     }
 }
 
 impl TerminalTrueColorFormatter {
-    pub fn new() -> Self {
-        Self::default()
+    pub const fn new() -> Self {
+        Self {
+            line_numbers: false,
+            code_tags: false,
+            custom_code_tags: Vec::new(),
+            style: None,
+            options: BTreeMap::new(),
+        }
     }
 }
 
@@ -208,11 +1307,31 @@ impl PygmentizeFormatter for TerminalTrueColorFormatter {
     const SHORT_NAME: &'static str = "terminal16m";
 
     fn options_str(&self) -> Option<Cow<'_, str>> {
+        let mut opts = Options::new();
+
         if self.line_numbers {
-            Some(Cow::Borrowed("linenos=true"))
-        } else {
-            None
+            opts.push("linenos", "true");
         }
+
+        if let Some(style) = &self.style {
+            opts.push("style", style);
+        }
+
+        opts.extend(&self.options);
+
+        opts.finish()
+    }
+
+    fn validate(&self) -> Result<(), PygmentizeError> {
+        validate_options(&self.options)
+    }
+
+    fn style(&self) -> Option<&str> {
+        self.style.as_deref()
+    }
+
+    fn filters(&self) -> Cow<'_, [Filter]> {
+        code_tags_filters(self.code_tags, &self.custom_code_tags)
     }
 }
 
@@ -222,23 +1341,37 @@ impl PygmentizeFormatter for TerminalTrueColorFormatter {
 ///
 /// See <https://pygments.org/docs/formatters/#Terminal256Formatter>
 /// for more information.
-#[derive(Clone, Debug)]
+#[derive(Clone, Hash, Debug)]
 pub struct Terminal256Formatter {
     /// Output line numbers.
     pub line_numbers: bool,
+    /// Highlight code tags in comments, see [`HtmlFormatter::code_tags`].
+    pub code_tags: bool,
+    /// Code tags to highlight instead of the default tags,
+    /// see [`HtmlFormatter::custom_code_tags`].
+    pub custom_code_tags: Vec<String>,
+    /// The style, i.e. color scheme, see [`HtmlFormatter::style`].
+    pub style: Option<String>,
+    /// Additional options passed to `pygmentize`, see
+    /// [`HtmlFormatter::options`].
+    pub options: BTreeMap<String, String>,
 }
 
 impl Default for Terminal256Formatter {
     fn default() -> Self {
-        Self {
-            line_numbers: false,
-        }
+        Self::new()
     }
 }
 
 impl Terminal256Formatter {
-    pub fn new() -> Self {
-        Self::default()
+    pub const fn new() -> Self {
+        Self {
+            line_numbers: false,
+            code_tags: false,
+            custom_code_tags: Vec::new(),
+            style: None,
+            options: BTreeMap::new(),
+        }
     }
 }
 
@@ -246,10 +1379,604 @@ impl PygmentizeFormatter for Terminal256Formatter {
     const SHORT_NAME: &'static str = "terminal256";
 
     fn options_str(&self) -> Option<Cow<'_, str>> {
+        let mut opts = Options::new();
+
         if self.line_numbers {
-            Some(Cow::Borrowed("linenos=true"))
-        } else {
-            None
+            opts.push("linenos", "true");
+        }
+
+        if let Some(style) = &self.style {
+            opts.push("style", style);
+        }
+
+        opts.extend(&self.options);
+
+        opts.finish()
+    }
+
+    fn validate(&self) -> Result<(), PygmentizeError> {
+        validate_options(&self.options)
+    }
+
+    fn style(&self) -> Option<&str> {
+        self.style.as_deref()
+    }
+
+    fn filters(&self) -> Cow<'_, [Filter]> {
+        code_tags_filters(self.code_tags, &self.custom_code_tags)
+    }
+}
+
+/// Format tokens as RTF, i.e. Rich Text Format, which can be pasted
+/// into e.g. Word, LibreOffice, or email clients.
+///
+/// See <https://pygments.org/docs/formatters/#RtfFormatter>
+/// for more information.
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::{PygmentizeError, RtfFormatter};
+///
+/// # fn main() -> Result<(), PygmentizeError> {
+/// let fmt = RtfFormatter {
+///     font_face: Some(String::from("Consolas")),
+///     ..RtfFormatter::default()
+/// };
+///
+/// let rtf = pygmentize::highlight("print('Hello World')", Some("python"), &fmt)?;
+/// assert!(rtf.starts_with(r"{\rtf1"));
+/// assert!(rtf.contains("Consolas;"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Hash, Debug)]
+pub struct RtfFormatter {
+    /// The style, i.e. color scheme, see [`HtmlFormatter::style`].
+    pub style: Option<String>,
+    /// The name of the font, e.g. `"Consolas"`. If `None`, Pygments
+    /// leaves the font to the application, which usually results in
+    /// a monospaced font.
+    ///
+    /// Cannot contain `,`, otherwise [`PygmentizeError::InvalidOption`]
+    /// is returned.
+    pub font_face: Option<String>,
+    /// Additional options passed to `pygmentize`, see
+    /// [`HtmlFormatter::options`].
+    pub options: BTreeMap<String, String>,
+}
+
+impl Default for RtfFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RtfFormatter {
+    pub const fn new() -> Self {
+        Self {
+            style: None,
+            font_face: None,
+            options: BTreeMap::new(),
+        }
+    }
+}
+
+impl PygmentizeFormatter for RtfFormatter {
+    const SHORT_NAME: &'static str = "rtf";
+
+    fn options_str(&self) -> Option<Cow<'_, str>> {
+        let mut opts = Options::new();
+
+        if let Some(style) = &self.style {
+            opts.push("style", style);
+        }
+
+        if let Some(font_face) = &self.font_face {
+            opts.push("fontface", font_face);
+        }
+
+        opts.extend(&self.options);
+
+        opts.finish()
+    }
+
+    fn validate(&self) -> Result<(), PygmentizeError> {
+        validate_no_comma("font_face", self.font_face.as_deref())?;
+        validate_options(&self.options)
+    }
+
+    fn style(&self) -> Option<&str> {
+        self.style.as_deref()
+    }
+}
+
+/// Format tokens with BBCode tags, e.g. `[color=#008000]fn[/color]`,
+/// which is supported by e.g. forums. Only colors, bold, italic,
+/// and underline are output.
+///
+/// See <https://pygments.org/docs/formatters/#BBCodeFormatter>
+/// for more information.
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::{BBCodeFormatter, PygmentizeError};
+///
+/// # fn main() -> Result<(), PygmentizeError> {
+/// let fmt = BBCodeFormatter {
+///     code_tag: true,
+///     style: Some(String::from("monokai")),
+///     ..BBCodeFormatter::default()
+/// };
+///
+/// let bbcode = pygmentize::highlight("x = 1", Some("python"), &fmt)?;
+/// assert!(bbcode.starts_with("[code][color=#f8f8f2]x[/color]"));
+/// assert!(bbcode.ends_with("[/code]\n"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Hash, Debug)]
+pub struct BBCodeFormatter {
+    /// Wrap the output in `[code]` tags, such that
+    /// whitespace is preserved and a monospaced font is used.
+    pub code_tag: bool,
+    /// The style, i.e. color scheme, see [`HtmlFormatter::style`].
+    pub style: Option<String>,
+    /// Additional options passed to `pygmentize`, see
+    /// [`HtmlFormatter::options`].
+    pub options: BTreeMap<String, String>,
+}
+
+impl Default for BBCodeFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BBCodeFormatter {
+    pub const fn new() -> Self {
+        Self {
+            code_tag: false,
+            style: None,
+            options: BTreeMap::new(),
+        }
+    }
+}
+
+impl PygmentizeFormatter for BBCodeFormatter {
+    const SHORT_NAME: &'static str = "bbcode";
+
+    fn options_str(&self) -> Option<Cow<'_, str>> {
+        let mut opts = Options::new();
+
+        if self.code_tag {
+            opts.push("codetag", "true");
+        }
+
+        if let Some(style) = &self.style {
+            opts.push("style", style);
+        }
+
+        opts.extend(&self.options);
+
+        opts.finish()
+    }
+
+    fn validate(&self) -> Result<(), PygmentizeError> {
+        validate_options(&self.options)
+    }
+
+    fn style(&self) -> Option<&str> {
+        self.style.as_deref()
+    }
+}
+
+/// Format tokens with IRC color sequences, i.e. mIRC color codes,
+/// e.g. for posting code in IRC channels. The colors are fixed,
+/// i.e. Pygments' styles are not supported.
+///
+/// See <https://pygments.org/docs/formatters/#IRCFormatter>
+/// for more information.
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::{IrcFormatter, PygmentizeError, PygmentizeFormatter};
+///
+/// # fn main() -> Result<(), PygmentizeError> {
+/// let fmt = IrcFormatter {
+///     line_numbers: true,
+///     ..IrcFormatter::default()
+/// };
+///
+/// let irc = fmt.highlight("x = 1", Some("python"))?;
+/// assert!(irc.starts_with("0001: x = \x0302"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Hash, Debug)]
+pub struct IrcFormatter {
+    /// Output line numbers.
+    pub line_numbers: bool,
+    /// The background of the IRC client, which the colors are chosen
+    /// for. The default is [`Background::Light`], like Pygments.
+    pub background: Background,
+    /// Additional options passed to `pygmentize`, see
+    /// [`HtmlFormatter::options`].
+    pub options: BTreeMap<String, String>,
+}
+
+impl Default for IrcFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IrcFormatter {
+    pub const fn new() -> Self {
+        Self {
+            line_numbers: false,
+            background: Background::Light,
+            options: BTreeMap::new(),
+        }
+    }
+}
+
+impl PygmentizeFormatter for IrcFormatter {
+    const SHORT_NAME: &'static str = "irc";
+
+    fn options_str(&self) -> Option<Cow<'_, str>> {
+        let mut opts = Options::new();
+
+        if self.line_numbers {
+            opts.push("linenos", "true");
+        }
+
+        if self.background != Background::Light {
+            opts.push("bg", self.background);
+        }
+
+        opts.extend(&self.options);
+
+        opts.finish()
+    }
+
+    fn validate(&self) -> Result<(), PygmentizeError> {
+        validate_options(&self.options)
+    }
+}
+
+/// Format tokens as the raw token stream, i.e. a line per token,
+/// with the token type and the Python `repr()` of its value separated
+/// by a tab, e.g. `Token.Keyword\t'fn'`.
+///
+/// Use [`tokenize()`](crate::tokenize) to get the tokens parsed.
+///
+/// See <https://pygments.org/docs/formatters/#RawTokenFormatter>
+/// for more information.
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::{PygmentizeError, PygmentizeFormatter, RawTokenFormatter};
+///
+/// # fn main() -> Result<(), PygmentizeError> {
+/// let raw = RawTokenFormatter::default().highlight("fn main() {}", Some("rust"))?;
+/// assert!(raw.starts_with("Token.Keyword\t'fn'\n"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Hash, Debug)]
+pub struct RawTokenFormatter {
+    /// Additional options passed to `pygmentize`, see
+    /// [`HtmlFormatter::options`].
+    pub options: BTreeMap<String, String>,
+}
+
+impl Default for RawTokenFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RawTokenFormatter {
+    pub const fn new() -> Self {
+        Self {
+            options: BTreeMap::new(),
+        }
+    }
+}
+
+impl PygmentizeFormatter for RawTokenFormatter {
+    const SHORT_NAME: &'static str = "raw";
+
+    fn options_str(&self) -> Option<Cow<'_, str>> {
+        let mut opts = Options::new();
+        opts.extend(&self.options);
+        opts.finish()
+    }
+
+    fn validate(&self) -> Result<(), PygmentizeError> {
+        validate_options(&self.options)
+    }
+}
+
+/// Format tokens as an image, e.g. a PNG, which requires the Python
+/// Imaging Library, i.e. `pip install Pillow`.
+///
+/// _The output is binary, so use
+/// [`highlight_bytes()`](crate::highlight_bytes), as
+/// [`highlight()`](crate::highlight) returns
+/// [`PygmentizeError::InvalidUtf8`]._
+///
+/// See <https://pygments.org/docs/formatters/#ImageFormatter>
+/// for more information.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use pygmentize::{ImageFormat, ImageFormatter};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let fmt = ImageFormatter {
+///     font_name: Some(String::from("DejaVu Sans Mono")),
+///     font_size: Some(24),
+///     image_format: Some(ImageFormat::Jpeg),
+///     ..ImageFormatter::default()
+/// };
+///
+/// let jpeg = pygmentize::highlight_bytes("fn main() {}", Some("rust"), &fmt)?;
+/// std::fs::write("main.jpg", jpeg)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Hash, Debug)]
+pub struct ImageFormatter {
+    /// Output line numbers.
+    ///
+    /// _Unlike Pygments, which defaults to outputting line numbers
+    /// for images, line numbers are only output if enabled._
+    pub line_numbers: bool,
+    /// The name of the font, e.g. `"DejaVu Sans Mono"`. If `None`,
+    /// Pygments uses a platform specific monospaced font.
+    ///
+    /// Cannot contain `,`, otherwise [`PygmentizeError::InvalidOption`]
+    /// is returned.
+    pub font_name: Option<String>,
+    /// The font size in points. Pygments defaults to `14`.
+    pub font_size: Option<usize>,
+    /// The image format. If `None`, PNG is output.
+    pub image_format: Option<ImageFormat>,
+    /// The style, i.e. color scheme, see [`HtmlFormatter::style`].
+    pub style: Option<String>,
+    /// Additional options passed to `pygmentize`, see
+    /// [`HtmlFormatter::options`].
+    pub options: BTreeMap<String, String>,
+}
+
+impl Default for ImageFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ImageFormatter {
+    pub const fn new() -> Self {
+        Self {
+            line_numbers: false,
+            font_name: None,
+            font_size: None,
+            image_format: None,
+            style: None,
+            options: BTreeMap::new(),
+        }
+    }
+}
+
+impl PygmentizeFormatter for ImageFormatter {
+    const SHORT_NAME: &'static str = "png";
+
+    fn options_str(&self) -> Option<Cow<'_, str>> {
+        let mut opts = Options::new();
+
+        opts.push("line_numbers", self.line_numbers);
+
+        if let Some(font_name) = &self.font_name {
+            opts.push("font_name", font_name);
+        }
+
+        if let Some(font_size) = self.font_size {
+            opts.push("font_size", font_size);
+        }
+
+        if let Some(image_format) = self.image_format {
+            opts.push("image_format", image_format);
+        }
+
+        if let Some(style) = &self.style {
+            opts.push("style", style);
+        }
+
+        opts.extend(&self.options);
+
+        opts.finish()
+    }
+
+    fn validate(&self) -> Result<(), PygmentizeError> {
+        validate_no_comma("font_name", self.font_name.as_deref())?;
+        validate_options(&self.options)
+    }
+
+    fn style(&self) -> Option<&str> {
+        self.style.as_deref()
+    }
+}
+
+/// How [`HtmlFormatter::line_numbers`] are output, see
+/// [`HtmlFormatter::line_number_mode`].
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Default, Debug)]
+pub enum LineNumbers {
+    /// The line numbers are output as text by Pygments, such that
+    /// they're selected and copied along with the code.
+    #[default]
+    Inline,
+    /// Every line is wrapped in a `<span class="line">`, and the line
+    /// numbers are output by `::before` pseudo-elements using a CSS
+    /// counter, i.e. they're never copied along with the code.
+    ///
+    /// The required CSS is included by
+    /// [`highlight_html_with_css()`](crate::highlight_html_with_css),
+    /// otherwise see [`line_number_counter_defs()`](crate::line_number_counter_defs).
+    CssCounter,
+}
+
+/// The background of the terminal, see [`TerminalFormatter::background`],
+/// or of the IRC client, see [`IrcFormatter::background`].
+///
+/// [`Terminal256Formatter`] and [`TerminalTrueColorFormatter`] use the
+/// colors of the style instead, i.e. for a light background use a style
+/// suited for it, e.g. `"solarized-light"`.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Default, Debug)]
+pub enum Background {
+    #[default]
+    Light,
+    Dark,
+}
+
+impl fmt::Display for Background {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Light => "light",
+            Self::Dark => "dark",
+        })
+    }
+}
+
+/// The image format output by [`ImageFormatter`].
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Gif,
+    Bmp,
+}
+
+impl fmt::Display for ImageFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Png => "png",
+            Self::Jpeg => "jpeg",
+            Self::Gif => "gif",
+            Self::Bmp => "bmp",
+        })
+    }
+}
+
+/// Returns the filters for the `code_tags` and `custom_code_tags`
+/// options, see [`HtmlFormatter::code_tags`].
+fn code_tags_filters(code_tags: bool, custom_code_tags: &[String]) -> Cow<'static, [Filter]> {
+    if code_tags {
+        Cow::Owned(vec![code_tags_filter(custom_code_tags)])
+    } else {
+        Cow::Borrowed(&[])
+    }
+}
+
+fn code_tags_filter(custom_code_tags: &[String]) -> Filter {
+    if custom_code_tags.is_empty() {
+        Filter::code_tags()
+    } else {
+        Filter::custom_code_tags(custom_code_tags)
+    }
+}
+
+/// Returns [`PygmentizeError::InvalidOption`] if the
+/// starting line number or the line number step is `0`.
+fn validate_line_numbers(start: Option<usize>, step: Option<usize>) -> Result<(), PygmentizeError> {
+    if start == Some(0) {
+        return Err(PygmentizeError::InvalidOption(
+            "line_number_start",
+            String::from("line numbers start at 1"),
+        ));
+    }
+
+    if step == Some(0) {
+        return Err(PygmentizeError::InvalidOption(
+            "line_number_step",
+            String::from("step must be at least 1"),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Returns [`PygmentizeError::InvalidOption`] if `value` contains `,`,
+/// i.e. if it cannot be passed as part of `-O key=value,...`.
+pub(crate) fn validate_no_comma(
+    name: &'static str,
+    value: Option<&str>,
+) -> Result<(), PygmentizeError> {
+    match value {
+        Some(value) if value.contains(',') => Err(PygmentizeError::InvalidOption(
+            name,
+            format!("`{value}` contains `,`"),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Returns [`PygmentizeError::InvalidOption`] if a key
+/// or value of the additional `options` contains `,`.
+pub(crate) fn validate_options(options: &BTreeMap<String, String>) -> Result<(), PygmentizeError> {
+    for (key, value) in options {
+        if key.contains(',') || value.contains(',') {
+            return Err(PygmentizeError::InvalidOption(
+                "options",
+                format!("`{key}={value}` contains `,`"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Builds the comma-separated `key=value` list passed to `-O`.
+///
+/// The options are sorted by key, such that the same options always
+/// result in the same string, regardless of the order they are pushed
+/// in. Pushing the same key again replaces the value, which matches
+/// `pygmentize`, where the last value wins.
+#[derive(Default, Debug)]
+pub(crate) struct Options(BTreeMap<Cow<'static, str>, String>);
+
+impl Options {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn push(&mut self, key: &'static str, value: impl fmt::Display) {
+        self.0.insert(Cow::Borrowed(key), value.to_string());
+    }
+
+    /// Pushes all `options`, replacing the values of existing keys.
+    pub(crate) fn extend(&mut self, options: &BTreeMap<String, String>) {
+        for (key, value) in options {
+            self.0.insert(Cow::Owned(key.clone()), value.clone());
+        }
+    }
+
+    pub(crate) fn finish(self) -> Option<Cow<'static, str>> {
+        if self.0.is_empty() {
+            return None;
+        }
+
+        let mut opts = String::new();
+        for (i, (key, value)) in self.0.iter().enumerate() {
+            if i > 0 {
+                opts.push(',');
+            }
+            write!(opts, "{key}={value}").unwrap();
         }
+        Some(Cow::Owned(opts))
     }
 }
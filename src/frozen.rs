@@ -0,0 +1,80 @@
+use std::borrow::Cow;
+use std::ffi::OsStr;
+
+use crate::{Filter, PygmentizeError, PygmentizeFormatter};
+
+/// A formatter with its [`options_str()`](PygmentizeFormatter::options_str)
+/// computed once, such that it is not recomputed for every call,
+/// see [`PygmentizeFormatter::frozen()`].
+///
+/// All other methods are forwarded to the wrapped formatter.
+#[derive(Clone, Hash, Debug)]
+pub struct FrozenFormatter<F> {
+    fmt: F,
+    options: Option<String>,
+}
+
+impl<F> FrozenFormatter<F>
+where
+    F: PygmentizeFormatter,
+{
+    pub fn new(fmt: F) -> Self {
+        let options = fmt.options_str().map(Cow::into_owned);
+        Self { fmt, options }
+    }
+
+    /// Returns the wrapped formatter.
+    pub fn get(&self) -> &F {
+        &self.fmt
+    }
+
+    /// Returns the wrapped formatter, e.g. to change its options,
+    /// after which it can be frozen again.
+    pub fn into_inner(self) -> F {
+        self.fmt
+    }
+}
+
+impl<F> PygmentizeFormatter for FrozenFormatter<F>
+where
+    F: PygmentizeFormatter,
+{
+    const SHORT_NAME: &'static str = F::SHORT_NAME;
+
+    fn options_str(&self) -> Option<Cow<'_, str>> {
+        self.options.as_deref().map(Cow::Borrowed)
+    }
+
+    fn style(&self) -> Option<&str> {
+        self.fmt.style()
+    }
+
+    fn validate(&self) -> Result<(), PygmentizeError> {
+        self.fmt.validate()
+    }
+
+    fn filters(&self) -> Cow<'_, [Filter]> {
+        self.fmt.filters()
+    }
+
+    fn verbatim_options(&self) -> Vec<(&'static str, &str)> {
+        self.fmt.verbatim_options()
+    }
+
+    fn validate_input(&self, code: &str) -> Result<(), PygmentizeError> {
+        self.fmt.validate_input(code)
+    }
+
+    fn post_process(&self, code: &str, output: String) -> Result<String, PygmentizeError> {
+        self.fmt.post_process(code, output)
+    }
+
+    fn post_process_with(
+        &self,
+        bin: Option<&OsStr>,
+        code: &str,
+        output: String,
+    ) -> Result<String, PygmentizeError> {
+        self.fmt.post_process_with(bin, code, output)
+    }
+}
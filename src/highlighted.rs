@@ -0,0 +1,157 @@
+use std::ops::Deref;
+use std::thread;
+
+use crate::{
+    get_style_defs, highlight, line_number_counter_defs, HtmlFormatter, PygmentizeError,
+    PygmentizeFormatter,
+};
+
+/// The style Pygments uses, if no style is specified.
+pub(crate) const DEFAULT_STYLE: &str = "default";
+
+/// The output of [`highlight_detailed()`], along
+/// with information about how it was produced.
+///
+/// Dereferences to the output, such that it can be
+/// used where a `&str` is expected.
+///
+/// ```rust
+/// use pygmentize::HtmlFormatter;
+///
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let highlighted = pygmentize::highlight_detailed("fn main() {}", Some("rust"), &HtmlFormatter::default())?;
+/// assert!(highlighted.starts_with("<div"));
+///
+/// fn print(html: impl AsRef<str>) {
+///     println!("{}", html.as_ref());
+/// }
+/// print(&highlighted);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Highlighted {
+    /// The output of the formatter.
+    pub output: String,
+    /// The name of the style that was applied, i.e. the style of the
+    /// formatter, or `"default"` if the formatter did not specify a
+    /// style, as that is the style Pygments then uses.
+    pub style: String,
+}
+
+/// Same as [`highlight()`], but returns [`Highlighted`], which
+/// includes the effective style along with the output.
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::HtmlFormatter;
+///
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let highlighted = pygmentize::highlight_detailed("fn main() {}", Some("rust"), &HtmlFormatter::default())?;
+/// assert_eq!(highlighted.style, "default");
+/// println!("{}", highlighted.output);
+/// # Ok(())
+/// # }
+/// ```
+pub fn highlight_detailed<F>(
+    code: impl AsRef<str>,
+    lang: Option<&str>,
+    fmt: &F,
+) -> Result<Highlighted, PygmentizeError>
+where
+    F: PygmentizeFormatter,
+{
+    let output = highlight(code, lang, fmt)?;
+    let style = fmt.style().unwrap_or(DEFAULT_STYLE).to_owned();
+    Ok(Highlighted { output, style })
+}
+
+impl Deref for Highlighted {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.output
+    }
+}
+
+impl AsRef<str> for Highlighted {
+    fn as_ref(&self) -> &str {
+        &self.output
+    }
+}
+
+/// The output of [`highlight_html_with_css()`], i.e. the HTML along
+/// with the CSS rules for the style of the formatter.
+///
+/// Dereferences to the HTML, such that it can be
+/// used where a `&str` is expected.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct HighlightedHtml {
+    pub html: String,
+    /// The CSS rules for the style, scoped to `.highlight`, or
+    /// [`HtmlFormatter::css_class`] if set. Includes the rules of
+    /// [`line_number_counter_defs()`](crate::line_number_counter_defs)
+    /// if [`LineNumbers::CssCounter`](crate::LineNumbers::CssCounter)
+    /// line numbers are enabled.
+    pub css: String,
+}
+
+/// Same as [`highlight()`], but also returns the CSS rules for the
+/// style of `fmt`, i.e. everything needed to render the HTML.
+///
+/// Pygments cannot output both in a single invocation, so `pygmentize`
+/// is executed twice, but concurrently, such that the overhead of
+/// the second invocation is mostly hidden.
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::HtmlFormatter;
+///
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let highlighted = pygmentize::highlight_html_with_css("fn main() {}", Some("rust"), &HtmlFormatter::default())?;
+/// assert!(highlighted.html.contains(r#"<span class="k">fn</span>"#));
+/// assert!(highlighted.css.contains(".highlight .k {"));
+/// # Ok(())
+/// # }
+/// ```
+pub fn highlight_html_with_css(
+    code: impl AsRef<str>,
+    lang: Option<&str>,
+    fmt: &HtmlFormatter,
+) -> Result<HighlightedHtml, PygmentizeError> {
+    let style = fmt.style().unwrap_or(DEFAULT_STYLE);
+    let selector = format!(".{}", fmt.css_class.as_deref().unwrap_or("highlight"));
+
+    let (html, css) = thread::scope(|s| {
+        let css = s.spawn(|| {
+            let mut css = get_style_defs(style, Some(&selector))?;
+            if fmt.css_line_numbers() {
+                css.push_str(&line_number_counter_defs(Some(&selector)));
+            }
+            Ok(css)
+        });
+        let html = highlight(code, lang, fmt);
+        (html, css.join().expect("style defs thread panicked"))
+    });
+
+    Ok(HighlightedHtml {
+        html: html?,
+        css: css?,
+    })
+}
+
+impl Deref for HighlightedHtml {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.html
+    }
+}
+
+impl AsRef<str> for HighlightedHtml {
+    fn as_ref(&self) -> &str {
+        &self.html
+    }
+}
@@ -0,0 +1,127 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+
+/// Lines to emphasize, given as individual lines and/or ranges of lines.
+/// Line numbers start at `1`, regardless of any custom starting line number.
+///
+/// Use the [`hl_lines!`](crate::hl_lines) macro to mix individual
+/// lines and ranges.
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::{hl_lines, HlLines};
+///
+/// let lines = hl_lines![5, 10..=15, 20];
+/// assert_eq!(lines.to_string(), "5 10 11 12 13 14 15 20");
+///
+/// let lines = HlLines::from(10..=12).with(20);
+/// assert_eq!(lines.to_string(), "10 11 12 20");
+///
+/// let lines = HlLines::from([1, 3, 5]);
+/// assert_eq!(lines.to_string(), "1 3 5");
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Default, Debug)]
+pub struct HlLines {
+    ranges: Vec<RangeInclusive<usize>>,
+}
+
+impl HlLines {
+    pub const fn new() -> Self {
+        Self { ranges: Vec::new() }
+    }
+
+    /// Adds `lines`, which can be a line, a range of lines,
+    /// or another [`HlLines`].
+    pub fn push(&mut self, lines: impl Into<HlLines>) {
+        self.ranges.extend(lines.into().ranges);
+    }
+
+    /// Same as [`push()`](Self::push), but returns `self`.
+    pub fn with(mut self, lines: impl Into<HlLines>) -> Self {
+        self.push(lines);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.iter().all(|range| range.is_empty())
+    }
+
+    /// Returns an iterator over all the individual lines.
+    pub fn lines(&self) -> impl Iterator<Item = usize> + '_ {
+        self.ranges.iter().flat_map(|range| range.clone())
+    }
+}
+
+/// Creates [`HlLines`] from a mix of individual lines and ranges of lines.
+///
+/// ```rust
+/// use pygmentize::hl_lines;
+///
+/// let lines = hl_lines![5, 10..=15, 20];
+/// assert_eq!(lines.to_string(), "5 10 11 12 13 14 15 20");
+/// ```
+#[macro_export]
+macro_rules! hl_lines {
+    ($($lines:expr),* $(,)?) => {{
+        #[allow(unused_mut)]
+        let mut hl_lines = $crate::HlLines::new();
+        $(hl_lines.push($lines);)*
+        hl_lines
+    }};
+}
+
+impl From<usize> for HlLines {
+    fn from(line: usize) -> Self {
+        Self::from(line..=line)
+    }
+}
+
+impl From<RangeInclusive<usize>> for HlLines {
+    fn from(range: RangeInclusive<usize>) -> Self {
+        Self {
+            ranges: vec![range],
+        }
+    }
+}
+
+impl<const N: usize> From<[usize; N]> for HlLines {
+    fn from(lines: [usize; N]) -> Self {
+        lines.into_iter().collect()
+    }
+}
+
+impl From<Vec<usize>> for HlLines {
+    fn from(lines: Vec<usize>) -> Self {
+        lines.into_iter().collect()
+    }
+}
+
+impl FromIterator<usize> for HlLines {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        Self {
+            ranges: iter.into_iter().map(|line| line..=line).collect(),
+        }
+    }
+}
+
+impl FromIterator<RangeInclusive<usize>> for HlLines {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<usize>>>(iter: I) -> Self {
+        Self {
+            ranges: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// Formats the lines space-separated, as expected by `hl_lines`.
+impl fmt::Display for HlLines {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, line) in self.lines().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
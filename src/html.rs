@@ -0,0 +1,691 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+
+use crate::{get_style_defs_with, PygmentizeError};
+
+/// Returns `true` if `code` looks like it is already the output of
+/// the `html` formatter, i.e. it contains the `<pre><span></span>`
+/// preamble or `<span>`s with Pygments' short token classes
+/// (e.g. `<span class="k">` or `<span class="s2">`).
+pub(crate) fn is_highlighted(code: &str) -> bool {
+    if code.contains("<pre><span></span>") {
+        return true;
+    }
+
+    code.match_indices("<span class=\"").any(|(i, tag)| {
+        let rest = &code[(i + tag.len())..];
+        match rest.split_once("\">") {
+            Some((class, _)) => is_token_class(class),
+            None => false,
+        }
+    })
+}
+
+fn is_token_class(class: &str) -> bool {
+    let mut chars = class.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_lowercase())
+        && (class.len() <= 3)
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+}
+
+/// Replaces the outermost `<div>` wrapper of `html` with `tag`,
+/// preserving its attributes and content.
+pub(crate) fn replace_root_element(html: &str, tag: &str) -> String {
+    let (Some(start), Some(end)) = (html.find("<div"), html.rfind("</div>")) else {
+        return html.to_owned();
+    };
+
+    let mut out = String::with_capacity(html.len() + (tag.len() * 2));
+    out.push_str(&html[..start]);
+    out.push('<');
+    out.push_str(tag);
+    out.push_str(&html[(start + "<div".len())..end]);
+    out.push_str("</");
+    out.push_str(tag);
+    out.push('>');
+    out.push_str(&html[(end + "</div>".len())..]);
+    out
+}
+
+/// Removes trailing whitespace from each line, which is either bare
+/// or within whitespace `<span class="w">` tokens. Whitespace within
+/// other tokens, e.g. comments and strings, is left as is.
+pub(crate) fn trim_line_trailing_whitespace(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    for (i, line) in html.split('\n').enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        out.push_str(trim_line_end(line));
+    }
+    out
+}
+
+fn trim_line_end(mut line: &str) -> &str {
+    loop {
+        let trimmed = line.trim_end_matches([' ', '\t']);
+        let trimmed = strip_whitespace_token_suffix(trimmed).unwrap_or(trimmed);
+        if trimmed.len() == line.len() {
+            return line;
+        }
+        line = trimmed;
+    }
+}
+
+fn strip_whitespace_token_suffix(line: &str) -> Option<&str> {
+    const WHITESPACE_TOKEN: &str = r#"<span class="w">"#;
+
+    let rest = line.strip_suffix("</span>")?;
+    let start = rest.rfind(WHITESPACE_TOKEN)?;
+    let ws = &rest[(start + WHITESPACE_TOKEN.len())..];
+    if ws.chars().all(|c| (c == ' ') || (c == '\t')) {
+        Some(&line[..start])
+    } else {
+        None
+    }
+}
+
+/// Prepends a `<style>` block with the rules of `style` to `html`, which
+/// are scoped to a class unique to the output, which is added to the
+/// outermost `<div>` wrapper.
+///
+/// The class is derived from a hash of the output and the style, such
+/// that separately highlighted fragments do not collide, even across
+/// processes, while identical fragments share identical rules.
+pub(crate) fn add_scoped_style(
+    bin: Option<&OsStr>,
+    html: &str,
+    style: &str,
+    line_number_counters: bool,
+) -> Result<String, PygmentizeError> {
+    let mut hasher = DefaultHasher::new();
+    html.hash(&mut hasher);
+    style.hash(&mut hasher);
+    let class = format!("highlight-{:016x}", hasher.finish());
+
+    let selector = format!(".{class}");
+    let mut css = scope_style_defs(
+        &get_style_defs_with(bin, style, Some(&selector))?,
+        &selector,
+    );
+    if line_number_counters {
+        css.push_str(&line_number_counter_defs(Some(&selector)));
+    }
+
+    let html = add_wrapper_class(html, &class)
+        .unwrap_or_else(|| format!(r#"<div class="{class}">{html}</div>"#));
+
+    Ok(format!("<style>\n{css}</style>\n{html}"))
+}
+
+/// Returns the CSS rules for
+/// [`LineNumbers::CssCounter`](crate::LineNumbers::CssCounter),
+/// i.e. which number the lines using a CSS counter, prefixed with
+/// `selector`, e.g. `Some(".highlight")`.
+///
+/// The numbers are output by `::before` pseudo-elements, which are
+/// not selectable, i.e. they are never copied along with the code.
+///
+/// # Example
+///
+/// ```rust
+/// let css = pygmentize::line_number_counter_defs(Some(".highlight"));
+/// assert!(css.starts_with(".highlight pre { counter-reset: line; }"));
+/// ```
+pub fn line_number_counter_defs(selector: Option<&str>) -> String {
+    let prefix = selector
+        .map(|selector| format!("{selector} "))
+        .unwrap_or_default();
+    format!(
+        "{prefix}pre {{ counter-reset: line; }}\n\
+         {prefix}pre span.line::before {{ \
+         counter-increment: line; content: counter(line); \
+         display: inline-block; min-width: 2em; padding-right: 1em; \
+         text-align: right; opacity: 0.5; user-select: none; }}\n"
+    )
+}
+
+/// Sets the initial value of the line counter of
+/// [`LineNumbers::CssCounter`](crate::LineNumbers::CssCounter)
+/// on the `<pre>`, such that the first line is numbered `start`.
+pub(crate) fn set_line_counter_start(html: &str, start: usize) -> String {
+    let Some(pre) = html.find("<pre") else {
+        return html.to_owned();
+    };
+    let end = pre + html[pre..].find('>').unwrap_or(html.len() - pre);
+    let reset = format!("counter-reset: line {}", start.saturating_sub(1));
+
+    let mut out = html.to_owned();
+    match html[pre..end].find(r#"style=""#) {
+        // e.g. `<pre style="line-height: 125%;">` with `noclasses`
+        Some(style) => out.insert_str(pre + style + r#"style=""#.len(), &format!("{reset}; ")),
+        None => out.insert_str(end, &format!(r#" style="{reset}""#)),
+    }
+    out
+}
+
+/// Replaces the `<span id="line-N">` wrappers output by `linespans`
+/// with `<span class="line">`, such that multiple blocks on the same
+/// page don't result in duplicate ids.
+pub(crate) fn line_spans_to_class(html: &str) -> String {
+    const OPEN: &str = r#"<span id="line-"#;
+
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(i) = rest.find(OPEN) {
+        let digits = rest[i + OPEN.len()..]
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(0);
+        let end = i + OPEN.len() + digits;
+        if digits == 0 || !rest[end..].starts_with(r#"">"#) {
+            out.push_str(&rest[..end]);
+            rest = &rest[end..];
+            continue;
+        }
+        out.push_str(&rest[..i]);
+        out.push_str(r#"<span class="line">"#);
+        rest = &rest[end + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Adds the `hl-{language}` class to the outermost `<div>`
+/// wrapper, like nbconvert does for notebook cells.
+pub(crate) fn add_jupyter_class(html: &str, language: &str) -> String {
+    add_wrapper_class(html, &format!("hl-{language}")).unwrap_or_else(|| html.to_owned())
+}
+
+/// Adds `class` to the classes of the outermost `<div>` wrapper, i.e.
+/// `<div class="highlight">` or the class set with `cssclass`.
+/// Returns `None` if there is no wrapper.
+fn add_wrapper_class(html: &str, class: &str) -> Option<String> {
+    const WRAPPER: &str = r#"<div class=""#;
+
+    let start = html.find(WRAPPER)? + WRAPPER.len();
+    let end = start + html[start..].find('"')?;
+
+    let mut out = html.to_owned();
+    out.insert_str(end, &format!(" {class}"));
+    Some(out)
+}
+
+/// Prefixes the rules, which Pygments does not prefix with
+/// `selector`, e.g. `pre { ... }` and `td.linenos .normal { ... }`.
+fn scope_style_defs(css: &str, selector: &str) -> String {
+    let mut out = String::with_capacity(css.len() + (selector.len() * 8));
+    for line in css.lines() {
+        if !line.is_empty() && !line.starts_with(selector) {
+            out.push_str(selector);
+            out.push(' ');
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out
+}
+
+/// Returns the set of classes used in `html`, e.g. the output of
+/// [`HtmlFormatter`](crate::HtmlFormatter). This includes token classes
+/// (e.g. `k` and `s2`), as well as e.g. `highlight`, `linenos`, and `hll`.
+///
+/// Combined with the style's rules, this can be used to produce a
+/// stylesheet only containing the rules that are actually used.
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::HtmlFormatter;
+///
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let fmt = HtmlFormatter {
+///     line_numbers: true,
+///     ..HtmlFormatter::default()
+/// };
+///
+/// let html = pygmentize::highlight("fn main() {}", Some("rust"), &fmt)?;
+///
+/// let classes = pygmentize::used_classes(&html);
+/// assert!(classes.contains("highlight"));
+/// assert!(classes.contains("linenos"));
+/// assert!(classes.contains("k"));
+/// assert!(!classes.contains("s2"));
+/// # Ok(())
+/// # }
+/// ```
+pub fn used_classes(html: &str) -> HashSet<String> {
+    const CLASS_ATTR: &str = "class=\"";
+
+    html.match_indices(CLASS_ATTR)
+        .filter_map(|(i, _)| {
+            let rest = &html[(i + CLASS_ATTR.len())..];
+            rest.split_once('"').map(|(classes, _)| classes)
+        })
+        .flat_map(str::split_whitespace)
+        .map(str::to_owned)
+        .collect()
+}
+
+/// Normalizes the HTML output of Pygments, such that snapshots of it
+/// are stable across Pygments versions, where only the semantic content
+/// is kept, i.e. differences in how tokens are split, and in whitespace
+/// between tags, are removed. This:
+///
+/// - Removes comments, e.g. `<!-- pygments 2.15.1 -->`.
+/// - Removes the empty `<span></span>` at the start of `<pre>`.
+/// - Unwraps whitespace spans, i.e. `<span class="w">`, which older
+///   versions of Pygments do not output.
+/// - Merges adjacent spans with the same classes.
+/// - Sorts the classes within `class` attributes.
+/// - Removes whitespace between tags outside of `<pre>`, as well
+///   as trailing whitespace.
+///
+/// _The result is meant for comparing, not for rendering._
+///
+/// # Example
+///
+/// ```rust
+/// let old = r#"<div class="highlight"><pre><span class="k">fn</span> <span class="nf">main</span>
+/// </pre></div>
+/// "#;
+/// let new = r#"<!-- pygments 2.15.1 -->
+/// <div class="highlight"><pre><span></span><span class="k">fn</span><span class="w"> </span><span class="nf">ma</span><span class="nf">in</span>
+/// </pre></div>"#;
+///
+/// assert_eq!(
+///     pygmentize::normalize_for_snapshot(old),
+///     pygmentize::normalize_for_snapshot(new),
+/// );
+/// ```
+pub fn normalize_for_snapshot(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+
+    // For each open `<span>`, whether its tags are removed
+    let mut spans = Vec::new();
+    // The last `<span>` tag, if nothing but its `</span>` was output since
+    let mut last_closed: Option<String> = None;
+    let mut pre_depth = 0usize;
+
+    let mut rest = html;
+    while !rest.is_empty() {
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.split_once("-->").map_or("", |(_, rest)| rest);
+            continue;
+        }
+
+        if !rest.starts_with('<') {
+            let end = rest.find('<').unwrap_or(rest.len());
+            let text = &rest[..end];
+            rest = &rest[end..];
+
+            if (pre_depth == 0) && text.trim().is_empty() {
+                continue;
+            }
+            out.push_str(text);
+            last_closed = None;
+            continue;
+        }
+
+        let end = rest.find('>').map_or(rest.len(), |end| end + 1);
+        let tag = sort_classes(&rest[..end]);
+        rest = &rest[end..];
+
+        if tag == "</span>" {
+            match spans.pop() {
+                Some((true, _)) => {}
+                Some((false, open)) => {
+                    out.push_str(&tag);
+                    last_closed = Some(open);
+                }
+                None => out.push_str(&tag),
+            }
+            continue;
+        }
+
+        if tag.starts_with("<span") {
+            if (tag == "<span>") || (tag == r#"<span class="w">"#) {
+                spans.push((true, tag));
+            } else if last_closed.as_deref() == Some(&tag) {
+                // Reopen the previous span, by removing its `</span>`
+                out.truncate(out.len() - "</span>".len());
+                last_closed = None;
+                spans.push((false, tag));
+            } else {
+                out.push_str(&tag);
+                last_closed = None;
+                spans.push((false, tag));
+            }
+            continue;
+        }
+
+        if tag.starts_with("<pre") {
+            pre_depth += 1;
+        } else if tag == "</pre>" {
+            pre_depth = pre_depth.saturating_sub(1);
+        }
+        out.push_str(&tag);
+        last_closed = None;
+    }
+
+    out.truncate(out.trim_end().len());
+    out
+}
+
+/// Sorts the classes within the `class` attribute of `tag`.
+fn sort_classes(tag: &str) -> String {
+    const CLASS_ATTR: &str = "class=\"";
+
+    let Some(start) = tag.find(CLASS_ATTR).map(|i| i + CLASS_ATTR.len()) else {
+        return tag.to_owned();
+    };
+    let Some(len) = tag[start..].find('"') else {
+        return tag.to_owned();
+    };
+
+    let mut classes = tag[start..(start + len)]
+        .split_whitespace()
+        .collect::<Vec<_>>();
+    classes.sort_unstable();
+
+    format!(
+        "{}{}{}",
+        &tag[..start],
+        classes.join(" "),
+        &tag[(start + len)..]
+    )
+}
+
+/// Returns the source text of `html`, e.g. the output of
+/// [`HtmlFormatter`](crate::HtmlFormatter), i.e. the text of the code
+/// `<pre>` with all tags removed and entities unescaped. Line numbers,
+/// both in table and inline mode, are not included.
+///
+/// This reverses highlighting, e.g. for indexing or diffing, except for
+/// options which change the text itself, e.g.
+/// [`expand_tabs`](crate::HtmlFormatter::expand_tabs).
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::HtmlFormatter;
+///
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let fmt = HtmlFormatter {
+///     line_numbers: true,
+///     ..HtmlFormatter::default()
+/// };
+///
+/// let code = "let s = \"<a & b>\";\n";
+/// let html = pygmentize::highlight(code, Some("rust"), &fmt)?;
+/// assert_eq!(pygmentize::strip_highlight(&html), code);
+/// # Ok(())
+/// # }
+/// ```
+pub fn strip_highlight(html: &str) -> String {
+    let code = code_range(html).map_or(html, |range| &html[range]);
+
+    let mut text = String::with_capacity(code.len());
+    let mut rest = code;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let end = rest.find('>').map_or(rest.len(), |end| end + 1);
+        let tag = &rest[..end];
+        rest = &rest[end..];
+
+        if tag.starts_with("<span") && tag.contains(r#"class="linenos"#) {
+            // Inline line numbers, which only contain the number
+            rest = rest.split_once("</span>").map_or("", |(_, after)| after);
+        } else if (tag == "</summary>") || (tag == "</details>") {
+            // The newlines of region marker lines are removed by `fold_regions`
+            text.push('\n');
+        }
+    }
+    text.push_str(rest);
+
+    unescape(&text)
+}
+
+/// Adds `role="code"` to the outermost `<div>` wrapper, and marks the
+/// line numbers with `aria-hidden="true"`, such that screen readers
+/// do not read the line numbers as part of the code.
+pub(crate) fn add_accessibility_attributes(html: &str) -> String {
+    const ARIA_HIDDEN: &str = r#"aria-hidden="true" "#;
+
+    let html = html.replacen(r#"<div class=""#, r#"<div role="code" class=""#, 1);
+    html.replace(
+        r#"<span class="linenos"#,
+        &format!(r#"<span {ARIA_HIDDEN}class="linenos"#),
+    )
+    .replace(
+        r#"<td class="linenos"#,
+        &format!(r#"<td {ARIA_HIDDEN}class="linenos"#),
+    )
+}
+
+/// Replaces the HTML 4 conventions of a full document (which Pygments
+/// outputs with the `full` option) with their HTML5 equivalents, i.e.
+/// the HTML 4.01 doctype, `<meta http-equiv="content-type">`, and the
+/// `type` attribute of `<style>`. Fragments are left as is.
+pub(crate) fn to_html5(html: &str) -> String {
+    const DOCTYPE: &str = "<!DOCTYPE html PUBLIC";
+
+    let mut out = html.to_owned();
+
+    if let Some(start) = out.find(DOCTYPE) {
+        if let Some(len) = out[start..].find('>') {
+            out.replace_range(start..=(start + len), "<!DOCTYPE html>");
+        }
+    }
+
+    out.replacen(
+        r#"<meta http-equiv="content-type" content="text/html; charset=utf-8">"#,
+        r#"<meta charset="utf-8">"#,
+        1,
+    )
+    .replacen(r#"<style type="text/css">"#, "<style>", 1)
+}
+
+/// Wraps regions, i.e. lines between region markers such as
+/// `// region` and `// endregion`, in `<details>`, where the
+/// start marker line is the `<summary>`, see [`region_marker()`].
+///
+/// Only the content of the `<pre>` is changed, and lines are split
+/// such that `<span class="hll">` spans are kept intact.
+pub(crate) fn fold_regions(html: &str) -> String {
+    let Some(Range { start, end }) = code_range(html) else {
+        return html.to_owned();
+    };
+
+    let mut lines: Vec<String> = Vec::new();
+    for line in html[start..end].split_inclusive('\n') {
+        // `hl_lines` closes `<span class="hll">` after the newline
+        if let (Some(rest), Some(prev)) = (line.strip_prefix("</span>"), lines.last_mut()) {
+            if prev.contains(r#"<span class="hll">"#) {
+                prev.push_str("</span>");
+                lines.push(rest.to_owned());
+                continue;
+            }
+        }
+        lines.push(line.to_owned());
+    }
+
+    // Pairs of (start line, end line), where unmatched markers are ignored
+    let mut starts = Vec::new();
+    let mut regions = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        match region_marker(line) {
+            Some(Region::Start) => starts.push(i),
+            Some(Region::End) => {
+                if let Some(start) = starts.pop() {
+                    regions.push((start, i));
+                }
+            }
+            None => {}
+        }
+    }
+
+    if regions.is_empty() {
+        return html.to_owned();
+    }
+
+    let mut out = String::with_capacity(html.len() + (regions.len() * 48));
+    out.push_str(&html[..start]);
+    for (i, line) in lines.iter().enumerate() {
+        if regions.iter().any(|&(start, _)| start == i) {
+            // `<details>` and `<summary>` are block elements,
+            // so the newlines of the marker lines are removed
+            out.push_str("<details><summary>");
+            out.push_str(&line.replacen('\n', "", 1));
+            out.push_str("</summary>");
+        } else if regions.iter().any(|&(_, end)| end == i) {
+            out.push_str(&line.replacen('\n', "", 1));
+            out.push_str("</details>");
+        } else {
+            out.push_str(line);
+        }
+    }
+    out.push_str(&html[end..]);
+    out
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum Region {
+    Start,
+    End,
+}
+
+/// Returns whether the highlighted `line` is a region marker, i.e. a
+/// comment containing `region` or `endregion`, optionally prefixed with
+/// `#`, e.g. `// region`, `// #region`, `# region`, `#region` (C#),
+/// `#pragma region` (C++), `<!-- #region -->`, or `-- region` (SQL, Lua).
+///
+/// The language is not known, so any common comment
+/// prefix is accepted, regardless of the language.
+fn region_marker(line: &str) -> Option<Region> {
+    let text = unescape(&strip_tags(line));
+    let text = text.trim();
+
+    let text = ["<!--", "//", "/*", "--", "#", ";", "'", "%"]
+        .iter()
+        .find_map(|prefix| text.strip_prefix(prefix))?;
+    let text = text.trim_start();
+    let text = text.strip_prefix('#').unwrap_or(text);
+    let text = text.strip_prefix("pragma ").unwrap_or(text);
+    let text = text.to_ascii_lowercase();
+
+    let (region, rest) = if let Some(rest) = text.strip_prefix("endregion") {
+        (Region::End, rest)
+    } else if let Some(rest) = text.strip_prefix("region") {
+        (Region::Start, rest)
+    } else {
+        return None;
+    };
+
+    // e.g. `// regional` is not a marker
+    if rest.starts_with(|c: char| c.is_alphanumeric() || (c == '_')) {
+        return None;
+    }
+    Some(region)
+}
+
+/// Removes all tags from `html`, leaving only the text.
+fn strip_tags(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text
+}
+
+/// Reverses the escaping of Pygments' `html` formatter.
+pub(crate) fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Escapes `text` like Pygments' `html` formatter, i.e. the
+/// reverse of [`unescape()`].
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Appends the HTML-escaped `source` in a `<script type="text/plain">`.
+/// As `<` is escaped, the source cannot contain `</script` and break out
+/// of the element. For full documents, it is inserted before `</body>`.
+pub(crate) fn embed_source(html: &str, source: &str) -> String {
+    let script = format!(
+        r#"<script type="text/plain" class="raw-source">{}</script>"#,
+        escape(source)
+    );
+
+    let mut out = html.to_owned();
+    match out.rfind("</body>") {
+        Some(end) => out.insert_str(end, &format!("{script}\n")),
+        None => {
+            if !out.ends_with('\n') {
+                out.push('\n');
+            }
+            out.push_str(&script);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Escapes `title` for Pygments' `title` option, which is inserted into
+/// the document as is. Commas are escaped as well, as options are comma
+/// separated, and `pygmentize` provides no way to escape them.
+pub(crate) fn escape_title(title: &str) -> String {
+    escape(title).replace(',', "&#44;")
+}
+
+/// Returns the range of the content of the `<pre>` containing
+/// the code, i.e. skipping the line numbers in table mode.
+pub(crate) fn code_range(html: &str) -> Option<Range<usize>> {
+    // In table mode, the first `<pre>` contains the line numbers
+    let offset = html.find(r#"<td class="code""#).unwrap_or(0);
+    let pre = offset + html[offset..].find("<pre")?;
+    let start = pre + html[pre..].find('>')? + 1;
+    let end = html.rfind("</pre>")?;
+    (start <= end).then_some(start..end)
+}
+
+/// Replaces tabs in the text of `html` with `tab_size` spaces,
+/// while tabs within tags, e.g. in attributes, are left as is.
+pub(crate) fn expand_tabs(html: &str, tab_size: usize) -> String {
+    let spaces = " ".repeat(tab_size);
+
+    let mut out = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            '\t' if !in_tag => {
+                out.push_str(&spaces);
+                continue;
+            }
+            _ => {}
+        }
+        out.push(c);
+    }
+    out
+}
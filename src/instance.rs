@@ -0,0 +1,172 @@
+use std::ffi::OsString;
+use std::time::Duration;
+
+use crate::lexers::{is_valid_lexer_with, list_lexers_with};
+use crate::version::version_with;
+use crate::{
+    get_style_defs_with, highlight_bytes_with, highlight_with, Control, LexerInfo, LexerOptions,
+    PygmentizeError, PygmentizeFormatter, Version,
+};
+
+/// Configuration for executing `pygmentize`, which is used instead of the
+/// global configuration, e.g. [`set_bin_path()`](crate::set_bin_path),
+/// such that different parts of an application, or tests running in
+/// parallel, can use different configurations.
+///
+/// Settings which are not set fall back to the global configuration,
+/// i.e. [`Pygmentize::new()`] behaves the same as [`highlight()`](crate::highlight).
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::{HtmlFormatter, LexerOptions, Pygmentize};
+///
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let pygmentize = Pygmentize::new()
+///     .bin_path("pygmentize")
+///     .lexer_options(LexerOptions {
+///         tab_size: Some(4),
+///         ..LexerOptions::default()
+///     });
+///
+/// let html = pygmentize.highlight("fn main() {}", Some("rust"), &HtmlFormatter::default())?;
+/// println!("{html}");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Default, Debug)]
+pub struct Pygmentize {
+    bin: Option<OsString>,
+    lexer_options: Option<LexerOptions>,
+    timeout: Option<Duration>,
+}
+
+impl Pygmentize {
+    pub const fn new() -> Self {
+        Self {
+            bin: None,
+            lexer_options: None,
+            timeout: None,
+        }
+    }
+
+    /// The path to the `pygmentize` binary. If not set, the binary set
+    /// with [`set_bin_path()`](crate::set_bin_path) is used, including
+    /// the fallback set with [`set_fallback_bin_path()`](crate::set_fallback_bin_path),
+    /// which is not used if this is set.
+    pub fn bin_path(mut self, bin: impl Into<OsString>) -> Self {
+        self.bin = Some(bin.into());
+        self
+    }
+
+    /// The lexer options, which override the defaults set with
+    /// [`set_lexer_options()`](crate::set_lexer_options), see
+    /// [`LexerOptions::merged()`].
+    pub fn lexer_options(mut self, options: LexerOptions) -> Self {
+        self.lexer_options = Some(options);
+        self
+    }
+
+    /// Kill `pygmentize` if it has not finished within `timeout`, and
+    /// return [`PygmentizeError::Timeout`], e.g. such that a hanging
+    /// `pygmentize` cannot block a request handler indefinitely.
+    /// See also [`highlight_controlled()`](crate::highlight_controlled).
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use pygmentize::{HtmlFormatter, Pygmentize};
+    ///
+    /// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+    /// let pygmentize = Pygmentize::new().timeout(Duration::from_secs(5));
+    ///
+    /// let html = pygmentize.highlight("fn main() {}", Some("rust"), &HtmlFormatter::default())?;
+    /// println!("{html}");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Same as [`highlight()`](crate::highlight), but using this configuration.
+    pub fn highlight<F>(
+        &self,
+        code: impl AsRef<str>,
+        lang: Option<&str>,
+        fmt: &F,
+    ) -> Result<String, PygmentizeError>
+    where
+        F: PygmentizeFormatter,
+    {
+        highlight_with(
+            self.bin.as_deref(),
+            code.as_ref(),
+            lang,
+            fmt,
+            self.lexer_options.as_ref(),
+            self.control(),
+        )
+    }
+
+    /// Same as [`highlight_bytes()`](crate::highlight_bytes), but using this configuration.
+    pub fn highlight_bytes<F>(
+        &self,
+        code: impl AsRef<[u8]>,
+        lang: Option<&str>,
+        fmt: &F,
+    ) -> Result<Vec<u8>, PygmentizeError>
+    where
+        F: PygmentizeFormatter,
+    {
+        highlight_bytes_with(
+            self.bin.as_deref(),
+            code.as_ref(),
+            lang,
+            fmt,
+            self.lexer_options.as_ref(),
+            self.control(),
+        )
+    }
+
+    /// Same as [`version()`](crate::version), but using this configuration.
+    ///
+    /// ```rust
+    /// use pygmentize::{Pygmentize, Version};
+    ///
+    /// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+    /// let pygmentize = Pygmentize::new().bin_path("pygmentize");
+    /// assert!(pygmentize.version()? >= Version::new(2, 0, 0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn version(&self) -> Result<Version, PygmentizeError> {
+        version_with(self.bin.as_deref())
+    }
+
+    /// Same as [`get_style_defs()`](crate::get_style_defs), but using this configuration.
+    pub fn get_style_defs(
+        &self,
+        style: &str,
+        selector: Option<&str>,
+    ) -> Result<String, PygmentizeError> {
+        get_style_defs_with(self.bin.as_deref(), style, selector)
+    }
+
+    /// Same as [`list_lexers()`](crate::list_lexers), but using this configuration.
+    pub fn list_lexers(&self) -> Result<Vec<LexerInfo>, PygmentizeError> {
+        list_lexers_with(self.bin.as_deref())
+    }
+
+    /// Same as [`is_valid_lexer()`](crate::is_valid_lexer), but using this configuration.
+    pub fn is_valid_lexer(&self, lang: &str) -> Result<bool, PygmentizeError> {
+        is_valid_lexer_with(self.bin.as_deref(), lang)
+    }
+
+    fn control(&self) -> Control<'static> {
+        Control {
+            timeout: self.timeout,
+            cancel: None,
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use std::fmt::Write;
+
+use crate::html::{add_accessibility_attributes, replace_root_element, set_line_counter_start};
+use crate::HtmlFormatter;
+
+/// Joins separately highlighted HTML `fragments`, e.g. chunks of a
+/// file with annotations in between, into a single listing with one
+/// `<div class="highlight">` wrapper, as if they were highlighted as
+/// a single block.
+///
+/// The wrapper of each fragment is removed, along with any line
+/// numbers. If `opts` has [`line_numbers`](HtmlFormatter::line_numbers)
+/// or [`copyable`](HtmlFormatter::copyable) enabled, then the lines are
+/// numbered continuously across the fragments, starting at
+/// [`line_number_start`](HtmlFormatter::line_number_start) or `1`.
+/// With [`LineNumbers::CssCounter`](crate::LineNumbers::CssCounter),
+/// the fragments must be highlighted using the same mode, such that
+/// their lines are wrapped for the counter.
+///
+/// The `copyable`, `accessibility`, `html5`, and `root_element` options
+/// of `opts` are applied to the joined wrapper. Options, which affect
+/// the content of the lines, e.g. `highlight_lines`, must be applied
+/// when highlighting the fragments.
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::HtmlFormatter;
+///
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let fmt = HtmlFormatter {
+///     line_numbers: true,
+///     ..HtmlFormatter::default()
+/// };
+///
+/// let fragments = [
+///     pygmentize::highlight("fn a() {}\nfn b() {}", Some("rust"), &fmt)?,
+///     pygmentize::highlight("fn c() {}", Some("rust"), &fmt)?,
+/// ];
+///
+/// let html = pygmentize::join_fragments(&fragments, &fmt);
+/// assert_eq!(html.matches(r#"<div class="highlight">"#).count(), 1);
+/// assert!(html.contains(r#"<span class="normal">3</span>"#));
+/// # Ok(())
+/// # }
+/// ```
+pub fn join_fragments(fragments: &[String], opts: &HtmlFormatter) -> String {
+    let mut code = String::new();
+    for fragment in fragments {
+        code.push_str(&strip_line_numbers(fragment_code(fragment)));
+        // With CSS counters, lines end with `\n</span>`
+        if !code.is_empty() && !code.trim_end_matches("</span>").ends_with('\n') {
+            code.push('\n');
+        }
+    }
+
+    let (code_start, code_end) = if opts.html5 {
+        ("<pre><span></span><code>", "</code></pre>")
+    } else {
+        ("<pre><span></span>", "</pre>")
+    };
+
+    let class = opts.css_class.as_deref().unwrap_or("highlight");
+
+    let mut out = String::with_capacity(code.len() + 256);
+    write!(out, r#"<div class="{class}">"#).unwrap();
+
+    if opts.css_line_numbers() {
+        let mut pre = format!("{code_start}{code}{code_end}");
+        if let Some(start) = opts.line_number_start {
+            pre = set_line_counter_start(&pre, start);
+        }
+        out.push_str(&pre);
+    } else if opts.line_numbers || opts.copyable {
+        let start = opts.line_number_start.unwrap_or(1);
+        let end = start + code.matches('\n').count();
+        let width = (end - 1).to_string().len();
+
+        write!(
+            out,
+            r#"<table class="{class}table"><tr><td class="linenos"><div class="linenodiv"><pre>"#,
+        )
+        .unwrap();
+        for line in start..end {
+            if line > start {
+                out.push('\n');
+            }
+            write!(out, r#"<span class="normal">{line:>width$}</span>"#).unwrap();
+        }
+        out.push_str("</pre></div></td>");
+
+        out.push_str(if opts.copyable {
+            r#"<td class="code" data-code><div>"#
+        } else {
+            r#"<td class="code"><div>"#
+        });
+        out.push_str(code_start);
+        out.push_str(&code);
+        out.push_str(code_end);
+        out.push_str("</div></td></tr></table>");
+    } else {
+        out.push_str(code_start);
+        out.push_str(&code);
+        out.push_str(code_end);
+    }
+
+    out.push_str("</div>\n");
+
+    if opts.accessibility {
+        out = add_accessibility_attributes(&out);
+    }
+
+    if let Some(tag) = &opts.root_element {
+        out = replace_root_element(&out, tag);
+    }
+
+    out
+}
+
+/// Returns the content of the `<pre>` element containing the code,
+/// i.e. without the wrapper elements.
+fn fragment_code(html: &str) -> &str {
+    // In table mode, the first `<pre>` contains the line numbers
+    let html = match html.find(r#"<td class="code""#) {
+        Some(start) => &html[start..],
+        None => html,
+    };
+
+    let Some(start) = html.find("<pre") else {
+        return html;
+    };
+    let Some(len) = html[start..].find('>') else {
+        return html;
+    };
+    let code = &html[(start + len + 1)..];
+    let code = match code.rfind("</pre>") {
+        Some(end) => &code[..end],
+        None => code,
+    };
+
+    let code = code.strip_prefix("<span></span>").unwrap_or(code);
+    code.strip_prefix("<code>")
+        .and_then(|code| code.strip_suffix("</code>"))
+        .unwrap_or(code)
+}
+
+/// Removes inline line numbers, i.e. `<span class="linenos">1</span>`.
+fn strip_line_numbers(code: &str) -> String {
+    const LINENOS: &str = r#"class="linenos"#;
+
+    let mut out = String::with_capacity(code.len());
+    let mut rest = code;
+    while let Some(i) = rest.find(LINENOS) {
+        let (Some(start), Some(len)) = (rest[..i].rfind("<span"), rest[i..].find("</span>")) else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        rest = &rest[(i + len + "</span>".len())..];
+    }
+    out.push_str(rest);
+    out
+}
@@ -0,0 +1,158 @@
+use std::iter::Peekable;
+use std::str::Chars;
+
+#[derive(PartialEq, Clone, Debug)]
+pub(crate) enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    /// The members in the order they appear.
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub(crate) fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Self::Object(members) => members.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+/// A minimal JSON parser, for parsing the `--json` output of
+/// `pygmentize`. Returns `None` if `json` is not valid JSON.
+pub(crate) fn parse(json: &str) -> Option<Value> {
+    let mut chars = json.chars().peekable();
+    let value = parse_value(&mut chars)?;
+    skip_whitespace(&mut chars);
+    chars.next().is_none().then_some(value)
+}
+
+fn parse_value(chars: &mut Peekable<Chars<'_>>) -> Option<Value> {
+    skip_whitespace(chars);
+    match chars.peek()? {
+        '{' => {
+            chars.next();
+            let mut members = Vec::new();
+            if !consume(chars, '}') {
+                loop {
+                    skip_whitespace(chars);
+                    let key = parse_string(chars)?;
+                    if !consume(chars, ':') {
+                        return None;
+                    }
+                    members.push((key, parse_value(chars)?));
+                    if consume(chars, '}') {
+                        break;
+                    } else if !consume(chars, ',') {
+                        return None;
+                    }
+                }
+            }
+            Some(Value::Object(members))
+        }
+        '[' => {
+            chars.next();
+            let mut values = Vec::new();
+            if !consume(chars, ']') {
+                loop {
+                    values.push(parse_value(chars)?);
+                    if consume(chars, ']') {
+                        break;
+                    } else if !consume(chars, ',') {
+                        return None;
+                    }
+                }
+            }
+            Some(Value::Array(values))
+        }
+        '"' => parse_string(chars).map(Value::String),
+        't' => parse_literal(chars, "true", Value::Bool(true)),
+        'f' => parse_literal(chars, "false", Value::Bool(false)),
+        'n' => parse_literal(chars, "null", Value::Null),
+        _ => {
+            let mut number = String::new();
+            while let Some(&c) = chars.peek() {
+                if !matches!(c, '0'..='9' | '-' | '+' | '.' | 'e' | 'E') {
+                    break;
+                }
+                number.push(c);
+                chars.next();
+            }
+            number.parse().ok().map(Value::Number)
+        }
+    }
+}
+
+fn parse_string(chars: &mut Peekable<Chars<'_>>) -> Option<String> {
+    if chars.next()? != '"' {
+        return None;
+    }
+
+    let mut s = String::new();
+    loop {
+        match chars.next()? {
+            '"' => return Some(s),
+            '\\' => match chars.next()? {
+                'n' => s.push('\n'),
+                't' => s.push('\t'),
+                'r' => s.push('\r'),
+                'b' => s.push('\u{8}'),
+                'f' => s.push('\u{c}'),
+                'u' => {
+                    let mut code = parse_hex4(chars)?;
+                    // Characters outside the BMP are escaped as surrogate pairs
+                    if (0xD800..0xDC00).contains(&code) {
+                        if chars.next()? != '\\' || chars.next()? != 'u' {
+                            return None;
+                        }
+                        let low = parse_hex4(chars)?;
+                        if !(0xDC00..0xE000).contains(&low) {
+                            return None;
+                        }
+                        code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                    }
+                    s.push(char::from_u32(code)?);
+                }
+                c => s.push(c),
+            },
+            c => s.push(c),
+        }
+    }
+}
+
+fn parse_hex4(chars: &mut Peekable<Chars<'_>>) -> Option<u32> {
+    let hex = chars.take(4).collect::<String>();
+    if hex.len() != 4 {
+        return None;
+    }
+    u32::from_str_radix(&hex, 16).ok()
+}
+
+fn parse_literal(chars: &mut Peekable<Chars<'_>>, literal: &str, value: Value) -> Option<Value> {
+    for expected in literal.chars() {
+        if chars.next()? != expected {
+            return None;
+        }
+    }
+    Some(value)
+}
+
+/// Skips whitespace, and consumes `c` if it is next.
+fn consume(chars: &mut Peekable<Chars<'_>>, c: char) -> bool {
+    skip_whitespace(chars);
+    chars.next_if_eq(&c).is_some()
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars<'_>>) {
+    while chars.next_if(|c| c.is_ascii_whitespace()).is_some() {}
+}
@@ -0,0 +1,231 @@
+use std::fmt;
+
+/// Common languages, i.e. lexers, such that typos in their names are
+/// caught at compile time, while [`Lang::Custom`] allows any other
+/// lexer, see <https://pygments.org/languages/>.
+///
+/// [`highlight()`](crate::highlight) accepts a `Lang` directly, see
+/// [`IntoLang`], while the other functions take the language as
+/// `Option<&str>`, i.e. use [`Lang::as_alias()`].
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::{HtmlFormatter, Lang};
+///
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let html = pygmentize::highlight("fn main() {}", Lang::Rust, &HtmlFormatter::default())?;
+/// println!("{html}");
+///
+/// // As in Pygments, aliases are matched case-insensitively
+/// let lang = Lang::from("Rust");
+/// assert_eq!(lang, Lang::Rust);
+///
+/// let lang = Lang::from("zig");
+/// assert_eq!(lang, Lang::Custom(String::from("zig")));
+/// assert_eq!(lang.as_alias(), "zig");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+pub enum Lang {
+    Bash,
+    C,
+    Cpp,
+    CSharp,
+    Css,
+    Diff,
+    Dockerfile,
+    Go,
+    Haskell,
+    Html,
+    Java,
+    JavaScript,
+    Json,
+    Kotlin,
+    Lua,
+    Makefile,
+    Markdown,
+    Php,
+    Python,
+    Ruby,
+    Rust,
+    Scala,
+    Sql,
+    Swift,
+    Text,
+    Toml,
+    TypeScript,
+    Xml,
+    Yaml,
+    /// Any other lexer, given as its name or alias, e.g. `"zig"`.
+    Custom(String),
+}
+
+impl Lang {
+    /// All languages, except [`Lang::Custom`].
+    const KNOWN: [Lang; 29] = [
+        Self::Bash,
+        Self::C,
+        Self::Cpp,
+        Self::CSharp,
+        Self::Css,
+        Self::Diff,
+        Self::Dockerfile,
+        Self::Go,
+        Self::Haskell,
+        Self::Html,
+        Self::Java,
+        Self::JavaScript,
+        Self::Json,
+        Self::Kotlin,
+        Self::Lua,
+        Self::Makefile,
+        Self::Markdown,
+        Self::Php,
+        Self::Python,
+        Self::Ruby,
+        Self::Rust,
+        Self::Scala,
+        Self::Sql,
+        Self::Swift,
+        Self::Text,
+        Self::Toml,
+        Self::TypeScript,
+        Self::Xml,
+        Self::Yaml,
+    ];
+
+    /// Returns the alias of the lexer, which is passed
+    /// to `pygmentize -l`, e.g. `"rust"` or `"csharp"`.
+    pub fn as_alias(&self) -> &str {
+        match self {
+            Self::Bash => "bash",
+            Self::C => "c",
+            Self::Cpp => "cpp",
+            Self::CSharp => "csharp",
+            Self::Css => "css",
+            Self::Diff => "diff",
+            Self::Dockerfile => "docker",
+            Self::Go => "go",
+            Self::Haskell => "haskell",
+            Self::Html => "html",
+            Self::Java => "java",
+            Self::JavaScript => "javascript",
+            Self::Json => "json",
+            Self::Kotlin => "kotlin",
+            Self::Lua => "lua",
+            Self::Makefile => "make",
+            Self::Markdown => "markdown",
+            Self::Php => "php",
+            Self::Python => "python",
+            Self::Ruby => "ruby",
+            Self::Rust => "rust",
+            Self::Scala => "scala",
+            Self::Sql => "sql",
+            Self::Swift => "swift",
+            Self::Text => "text",
+            Self::Toml => "toml",
+            Self::TypeScript => "typescript",
+            Self::Xml => "xml",
+            Self::Yaml => "yaml",
+            Self::Custom(alias) => alias,
+        }
+    }
+}
+
+impl From<&str> for Lang {
+    /// Returns the known language with the alias, as returned by
+    /// [`Lang::as_alias()`] ignoring case, otherwise [`Lang::Custom`].
+    fn from(alias: &str) -> Self {
+        Self::KNOWN
+            .into_iter()
+            .find(|lang| lang.as_alias().eq_ignore_ascii_case(alias))
+            .unwrap_or_else(|| Self::Custom(alias.to_owned()))
+    }
+}
+
+impl From<String> for Lang {
+    fn from(alias: String) -> Self {
+        match Self::from(alias.as_str()) {
+            Self::Custom(_) => Self::Custom(alias),
+            lang => lang,
+        }
+    }
+}
+
+impl AsRef<str> for Lang {
+    fn as_ref(&self) -> &str {
+        self.as_alias()
+    }
+}
+
+impl fmt::Display for Lang {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_alias())
+    }
+}
+
+/// A language accepted by [`highlight()`](crate::highlight), i.e. a
+/// [`Lang`], an alias such as `"rust"`, or `Option<&str>`, where `None`
+/// guesses the language from the code.
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::{HtmlFormatter, Lang};
+///
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let fmt = HtmlFormatter::default();
+///
+/// let a = pygmentize::highlight("fn main() {}", Lang::Rust, &fmt)?;
+/// let b = pygmentize::highlight("fn main() {}", "rust", &fmt)?;
+/// let c = pygmentize::highlight("fn main() {}", Some("rust"), &fmt)?;
+/// assert!(a == b && b == c);
+///
+/// let guessed = pygmentize::highlight("#!/bin/sh\necho hi", None, &fmt)?;
+/// println!("{guessed}");
+/// # Ok(())
+/// # }
+/// ```
+pub trait IntoLang {
+    /// Returns the language, or `None` to guess it.
+    fn into_lang(self) -> Option<Lang>;
+}
+
+impl IntoLang for Lang {
+    fn into_lang(self) -> Option<Lang> {
+        Some(self)
+    }
+}
+
+impl IntoLang for &Lang {
+    fn into_lang(self) -> Option<Lang> {
+        Some(self.clone())
+    }
+}
+
+impl IntoLang for &str {
+    fn into_lang(self) -> Option<Lang> {
+        Some(Lang::from(self))
+    }
+}
+
+impl IntoLang for &String {
+    fn into_lang(self) -> Option<Lang> {
+        Some(Lang::from(self.as_str()))
+    }
+}
+
+impl IntoLang for String {
+    fn into_lang(self) -> Option<Lang> {
+        Some(Lang::from(self))
+    }
+}
+
+// Only `Option<&str>`, such that the type of `None` is inferred
+impl IntoLang for Option<&str> {
+    fn into_lang(self) -> Option<Lang> {
+        self.map(Lang::from)
+    }
+}
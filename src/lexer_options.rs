@@ -0,0 +1,225 @@
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+use crate::formatters::{validate_no_comma, validate_options, Options};
+use crate::{Filter, PygmentizeError};
+
+static LEXER_OPTIONS: RwLock<LexerOptions> = RwLock::new(LexerOptions::new());
+
+/// Options for the lexer, as opposed to the formatter. Options which are
+/// `None` are not passed to `pygmentize`, i.e. Pygments' default is used.
+///
+/// Set the defaults for all calls with [`set_lexer_options()`], and
+/// override them for a single call with [`highlight_with_lexer_options()`].
+///
+/// See <https://pygments.org/docs/lexers/> for available options.
+///
+/// [`highlight_with_lexer_options()`]: crate::highlight_with_lexer_options
+/// [`highlight_bytes()`]: crate::highlight_bytes
+#[derive(Clone, PartialEq, Eq, Hash, Default, Debug)]
+pub struct LexerOptions {
+    /// Strip leading and trailing newlines from the input.
+    /// Pygments defaults to `true`.
+    pub strip_newlines: Option<bool>,
+    /// Strip all leading and trailing whitespace from the input.
+    /// Pygments defaults to `false`.
+    pub strip_all: Option<bool>,
+    /// Ensure the input ends with a newline.
+    /// Pygments defaults to `true`.
+    pub ensure_newline: Option<bool>,
+    /// Expand tabs in the input to this many spaces.
+    /// Pygments defaults to `0`, i.e. tabs are not expanded.
+    pub tab_size: Option<usize>,
+    /// The encoding of the input, e.g. `"latin-1"` or `"shift_jis"`.
+    /// If `None`, Pygments decodes the input as UTF-8, falling back
+    /// to the encoding of the locale.
+    ///
+    /// As `&str` is always UTF-8, non-UTF-8 input must be given as
+    /// bytes, see [`highlight_bytes()`].
+    ///
+    /// ```rust
+    /// use pygmentize::{HtmlFormatter, LexerOptions, Pygmentize};
+    ///
+    /// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+    /// let pygmentize = Pygmentize::new().lexer_options(LexerOptions {
+    ///     input_encoding: Some(String::from("latin-1")),
+    ///     ..LexerOptions::default()
+    /// });
+    ///
+    /// // "café" encoded as Latin-1
+    /// let code = b"s = 'caf\xe9'";
+    /// let html = pygmentize.highlight_bytes(code, Some("python"), &HtmlFormatter::default())?;
+    /// assert!(String::from_utf8(html).unwrap().contains("café"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub input_encoding: Option<String>,
+    /// The encoding of the output, e.g. `"latin-1"`. If `None`, the
+    /// output is UTF-8, also if `input_encoding` is set (Pygments would
+    /// otherwise encode the output using the `input_encoding`).
+    ///
+    /// _Encodings other than UTF-8 require the bytes API, i.e.
+    /// [`highlight_bytes()`], as otherwise
+    /// [`PygmentizeError::InvalidUtf8`] is returned._
+    pub output_encoding: Option<String>,
+    /// Filters applied to the token stream output by the lexer, in
+    /// order, before the filters of the formatter, e.g.
+    /// `Filter::new("keywordcase").option("case", "upper")`,
+    /// see [`Filter::new()`].
+    pub filters: Vec<Filter>,
+    /// Additional options, e.g. lexer specific options such as
+    /// `startinline` for the PHP lexer. Neither keys nor values can
+    /// contain `,`, otherwise [`PygmentizeError::InvalidOption`]
+    /// is returned.
+    pub options: BTreeMap<String, String>,
+}
+
+impl LexerOptions {
+    pub const fn new() -> Self {
+        Self {
+            strip_newlines: None,
+            strip_all: None,
+            ensure_newline: None,
+            tab_size: None,
+            input_encoding: None,
+            output_encoding: None,
+            filters: Vec::new(),
+            options: BTreeMap::new(),
+        }
+    }
+
+    /// Returns `self` with the options set in `overrides` replacing
+    /// those in `self`. For the additional `options`, the entries
+    /// are merged, where entries in `overrides` replace those with
+    /// the same key in `self`. The `filters` of `overrides` are
+    /// applied after those of `self`.
+    ///
+    /// ```rust
+    /// use pygmentize::LexerOptions;
+    ///
+    /// let defaults = LexerOptions {
+    ///     tab_size: Some(4),
+    ///     strip_newlines: Some(false),
+    ///     ..LexerOptions::default()
+    /// };
+    /// let overrides = LexerOptions {
+    ///     tab_size: Some(8),
+    ///     ..LexerOptions::default()
+    /// };
+    ///
+    /// let merged = defaults.merged(&overrides);
+    /// assert_eq!(merged.tab_size, Some(8));
+    /// assert_eq!(merged.strip_newlines, Some(false));
+    /// ```
+    pub fn merged(&self, overrides: &LexerOptions) -> LexerOptions {
+        let mut options = self.options.clone();
+        options.extend(
+            overrides
+                .options
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone())),
+        );
+
+        LexerOptions {
+            strip_newlines: overrides.strip_newlines.or(self.strip_newlines),
+            strip_all: overrides.strip_all.or(self.strip_all),
+            ensure_newline: overrides.ensure_newline.or(self.ensure_newline),
+            tab_size: overrides.tab_size.or(self.tab_size),
+            input_encoding: overrides
+                .input_encoding
+                .clone()
+                .or_else(|| self.input_encoding.clone()),
+            output_encoding: overrides
+                .output_encoding
+                .clone()
+                .or_else(|| self.output_encoding.clone()),
+            filters: self
+                .filters
+                .iter()
+                .chain(&overrides.filters)
+                .cloned()
+                .collect(),
+            options,
+        }
+    }
+
+    fn validate(&self) -> Result<(), PygmentizeError> {
+        validate_no_comma("input_encoding", self.input_encoding.as_deref())?;
+        validate_no_comma("output_encoding", self.output_encoding.as_deref())?;
+
+        if let Some(part) = self.filters.iter().find_map(Filter::invalid_part) {
+            return Err(PygmentizeError::InvalidOption(
+                "lexer_options",
+                format!("filter `{part}` cannot be passed to `-F`"),
+            ));
+        }
+
+        validate_options(&self.options)
+    }
+
+    fn to_options_str(&self) -> Option<String> {
+        let mut opts = Options::new();
+
+        if let Some(strip_newlines) = self.strip_newlines {
+            opts.push("stripnl", strip_newlines);
+        }
+
+        if let Some(strip_all) = self.strip_all {
+            opts.push("stripall", strip_all);
+        }
+
+        if let Some(ensure_newline) = self.ensure_newline {
+            opts.push("ensurenl", ensure_newline);
+        }
+
+        if let Some(tab_size) = self.tab_size {
+            opts.push("tabsize", tab_size);
+        }
+
+        if let Some(encoding) = &self.input_encoding {
+            opts.push("encoding", encoding);
+        }
+
+        match &self.output_encoding {
+            Some(encoding) => opts.push("outencoding", encoding),
+            None if self.input_encoding.is_some() => opts.push("outencoding", "utf-8"),
+            None => {}
+        }
+
+        opts.extend(&self.options);
+
+        opts.finish().map(|opts| opts.into_owned())
+    }
+}
+
+/// Set the default lexer options, which are used by every call,
+/// unless overridden for a single call with
+/// [`highlight_with_lexer_options()`](crate::highlight_with_lexer_options).
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::LexerOptions;
+///
+/// pygmentize::set_lexer_options(LexerOptions {
+///     tab_size: Some(4),
+///     ..LexerOptions::default()
+/// });
+/// ```
+pub fn set_lexer_options(options: LexerOptions) {
+    *LEXER_OPTIONS.write().unwrap() = options;
+}
+
+/// Returns the `-O` options and the filters for the lexer, i.e. the
+/// defaults set with [`set_lexer_options()`] merged with `overrides`.
+pub(crate) fn lexer_args(
+    overrides: Option<&LexerOptions>,
+) -> Result<(Option<String>, Vec<Filter>), PygmentizeError> {
+    let defaults = LEXER_OPTIONS.read().unwrap();
+    let options = match overrides {
+        Some(overrides) => defaults.merged(overrides),
+        None => defaults.clone(),
+    };
+    options.validate()?;
+    Ok((options.to_options_str(), options.filters))
+}
@@ -0,0 +1,360 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashSet};
+use std::ffi::{OsStr, OsString};
+use std::path::Path;
+use std::sync::{Mutex, RwLock};
+
+use crate::json::{self, Value};
+use crate::version::version_with;
+use crate::{
+    bin_key, highlight, run_cmd, run_cmd_with_bin, PygmentizeError, PygmentizeFormatter, Version,
+};
+
+static ALIASES: RwLock<BTreeMap<String, String>> = RwLock::new(BTreeMap::new());
+
+/// The aliases of all lexers of a `pygmentize` binary,
+/// cached by [`is_valid_lexer()`].
+static LEXER_ALIASES: Mutex<Option<(OsString, HashSet<String>)>> = Mutex::new(None);
+
+/// Register `from` as an alias for the lexer `to`, such that e.g.
+/// `highlight(code, Some(from), fmt)` uses the lexer `to`.
+/// Aliases are matched case-insensitively.
+///
+/// Languages without a registered alias are passed
+/// to `pygmentize` unchanged.
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::HtmlFormatter;
+///
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// pygmentize::register_alias("node", "javascript");
+/// pygmentize::register_alias("c++", "cpp");
+///
+/// let html = pygmentize::highlight("let x = 1;", Some("node"), &HtmlFormatter::default())?;
+/// println!("{html}");
+/// # Ok(())
+/// # }
+/// ```
+pub fn register_alias(from: impl Into<String>, to: impl Into<String>) {
+    let mut from = from.into();
+    from.make_ascii_lowercase();
+    ALIASES.write().unwrap().insert(from, to.into());
+}
+
+/// Returns the lexer registered for `lang`
+/// using [`register_alias()`], otherwise `lang`.
+pub(crate) fn resolve_alias(lang: &str) -> Cow<'_, str> {
+    let aliases = ALIASES.read().unwrap();
+    if aliases.is_empty() {
+        return Cow::Borrowed(lang);
+    }
+
+    match aliases.get(&lang.to_ascii_lowercase()) {
+        Some(to) => Cow::Owned(to.clone()),
+        None => Cow::Borrowed(lang),
+    }
+}
+
+/// Guesses the lexer for `filename`, based solely on the filename,
+/// i.e. the file does not need to exist. If no specific lexer can
+/// be determined, then `"text"` is returned.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// assert_eq!(pygmentize::guess_lexer_for_filename("src/main.rs")?, "rust");
+/// assert_eq!(pygmentize::guess_lexer_for_filename("README")?, "text");
+/// # Ok(())
+/// # }
+/// ```
+pub fn guess_lexer_for_filename(filename: impl AsRef<Path>) -> Result<String, PygmentizeError> {
+    let filename = filename.as_ref();
+    let output = run_cmd([OsStr::new("-N"), filename.as_os_str()], None)?;
+    Ok(output.trim().to_owned())
+}
+
+/// How the lexer was guessed by [`guess_lexer()`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum GuessMethod {
+    /// The lexer was guessed from the filename.
+    Filename,
+    /// The lexer was guessed from the content, as the
+    /// filename alone resolved to `"text"`.
+    Content,
+    /// Neither the filename nor the content resolved to a specific
+    /// lexer, i.e. the lexer is `"text"`.
+    Fallback,
+}
+
+/// Guesses the lexer for a file, first from `filename`, see
+/// [`guess_lexer_for_filename()`], and if that resolves to `"text"`,
+/// e.g. for files without an extension, then from `content`, if given.
+/// Returns the lexer along with how it was guessed.
+///
+/// If no specific lexer can be determined, then `"text"` is returned
+/// along with [`GuessMethod::Fallback`].
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::GuessMethod;
+///
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let (lexer, method) = pygmentize::guess_lexer("main.rs", None)?;
+/// assert_eq!((lexer.as_str(), method), ("rust", GuessMethod::Filename));
+///
+/// let content = b"#!/bin/sh\necho Hello World\n";
+/// let (lexer, method) = pygmentize::guess_lexer("build", Some(content))?;
+/// assert_eq!((lexer.as_str(), method), ("bash", GuessMethod::Content));
+///
+/// let (lexer, method) = pygmentize::guess_lexer("notes", Some(b"Hello World"))?;
+/// assert_eq!((lexer.as_str(), method), ("text", GuessMethod::Fallback));
+/// # Ok(())
+/// # }
+/// ```
+pub fn guess_lexer(
+    filename: impl AsRef<Path>,
+    content: Option<&[u8]>,
+) -> Result<(String, GuessMethod), PygmentizeError> {
+    let lexer = guess_lexer_for_filename(filename)?;
+    if lexer != "text" {
+        return Ok((lexer, GuessMethod::Filename));
+    }
+
+    if let Some(content) = content {
+        let output = run_cmd(["-C"], Some(content))?;
+        let lexer = output.trim();
+        if lexer != "text" {
+            return Ok((lexer.to_owned(), GuessMethod::Content));
+        }
+    }
+
+    Ok((lexer, GuessMethod::Fallback))
+}
+
+/// Applies syntax highlighting to `code`, where the lexer is guessed
+/// from `filename`, see [`guess_lexer_for_filename()`]. The file does
+/// not need to exist, i.e. `code` is highlighted, not the contents
+/// of `filename`.
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::HtmlFormatter;
+///
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let html = pygmentize::highlight_named("fn main() {}", "main.rs", &HtmlFormatter::default())?;
+/// println!("{html}");
+/// # Ok(())
+/// # }
+/// ```
+pub fn highlight_named<F>(
+    code: impl AsRef<str>,
+    filename: impl AsRef<Path>,
+    fmt: &F,
+) -> Result<String, PygmentizeError>
+where
+    F: PygmentizeFormatter,
+{
+    let lang = guess_lexer_for_filename(filename)?;
+    highlight(code, &lang, fmt)
+}
+
+/// A lexer supported by Pygments, see [`list_lexers()`].
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct LexerInfo {
+    /// The human-readable name, e.g. `"Rust"`.
+    pub name: String,
+    /// The names accepted as `lang`, e.g. `["rust", "rs"]`.
+    pub aliases: Vec<String>,
+    /// The filename patterns, e.g. `["*.rs", "*.rs.in"]`.
+    pub filenames: Vec<String>,
+    /// The MIME types, e.g. `["text/rust", "text/x-rust"]`.
+    ///
+    /// Always empty before Pygments 2.11, see [`list_lexers()`].
+    pub mimetypes: Vec<String>,
+}
+
+/// Returns all lexers supported by the installed Pygments,
+/// sorted by name, e.g. for validating a language before
+/// calling [`highlight()`], or for populating a dropdown.
+///
+/// With Pygments 2.11 or newer `pygmentize -L lexers --json` is used,
+/// otherwise the plain `pygmentize -L lexers` output is parsed, which
+/// does not include the MIME types, i.e. [`LexerInfo::mimetypes`] is
+/// always empty.
+///
+/// Returns [`PygmentizeError::InvalidOutput`] if the
+/// output of `pygmentize -L lexers` cannot be parsed.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let lexers = pygmentize::list_lexers()?;
+///
+/// let rust = lexers.iter().find(|lexer| lexer.name == "Rust").unwrap();
+/// assert!(rust.aliases.contains(&String::from("rust")));
+/// assert!(rust.filenames.contains(&String::from("*.rs")));
+/// # Ok(())
+/// # }
+/// ```
+pub fn list_lexers() -> Result<Vec<LexerInfo>, PygmentizeError> {
+    list_lexers_with(None)
+}
+
+/// Same as [`list_lexers()`], but for `bin` instead of the global binary, if given.
+pub(crate) fn list_lexers_with(bin: Option<&OsStr>) -> Result<Vec<LexerInfo>, PygmentizeError> {
+    // `--json` was added in Pygments 2.11. If the version cannot be
+    // determined, then executing `pygmentize` reports the error instead
+    let json = matches!(version_with(bin), Ok(version) if version >= Version::new(2, 11, 0));
+
+    let args: &[&str] = if json {
+        &["-L", "lexers", "--json"]
+    } else {
+        &["-L", "lexers"]
+    };
+    let output = run_cmd_with_bin(bin, args, None)?;
+    let output = String::from_utf8(output).map_err(PygmentizeError::InvalidUtf8)?;
+
+    let lexers = if json {
+        parse_lexers_json(&output)
+    } else {
+        parse_lexers(&output)
+    };
+    lexers.ok_or(PygmentizeError::InvalidOutput(output))
+}
+
+/// Returns `true` if a lexer exists for `lang`, i.e. if `lang` is one of
+/// the aliases of [`list_lexers()`], after resolving aliases registered
+/// with [`register_alias()`]. As in Pygments, the comparison ignores case.
+///
+/// The aliases are only listed once per path to the `pygmentize`
+/// binary, and then cached.
+/// See also [`set_validate_language()`](crate::set_validate_language).
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// assert!(pygmentize::is_valid_lexer("rust")?);
+/// assert!(pygmentize::is_valid_lexer("Rust")?);
+/// assert!(!pygmentize::is_valid_lexer("not-a-language")?);
+/// # Ok(())
+/// # }
+/// ```
+pub fn is_valid_lexer(lang: &str) -> Result<bool, PygmentizeError> {
+    is_valid_lexer_with(None, lang)
+}
+
+/// Same as [`is_valid_lexer()`], but for `bin` instead of the global binary, if given.
+pub(crate) fn is_valid_lexer_with(
+    bin: Option<&OsStr>,
+    lang: &str,
+) -> Result<bool, PygmentizeError> {
+    let lang = resolve_alias(lang).to_lowercase();
+    let key = bin_key(bin);
+
+    let mut cache = LEXER_ALIASES.lock().unwrap();
+    if let Some((cached_bin, aliases)) = &*cache {
+        if *cached_bin == key {
+            return Ok(aliases.contains(&lang));
+        }
+    }
+
+    let aliases = list_lexers_with(bin)?
+        .into_iter()
+        .flat_map(|lexer| lexer.aliases)
+        .map(|alias| alias.to_lowercase())
+        .collect::<HashSet<_>>();
+    let valid = aliases.contains(&lang);
+    *cache = Some((key, aliases));
+
+    Ok(valid)
+}
+
+/// Parses the output of `pygmentize -L lexers`, where each lexer is
+/// listed as its aliases followed by an indented line of its name and
+/// filenames, if any, e.g.:
+///
+/// ```text
+/// * rust, rs:
+///     Rust (filenames *.rs, *.rs.in)
+/// ```
+fn parse_lexers(output: &str) -> Option<Vec<LexerInfo>> {
+    let mut lexers = Vec::new();
+
+    let mut lines = output.lines();
+    while let Some(line) = lines.next() {
+        // e.g. the version and the `Lexers:` heading
+        let Some(aliases) = line.strip_prefix("* ") else {
+            continue;
+        };
+        let aliases = aliases.strip_suffix(':')?;
+        let info = lines.next()?.strip_prefix("    ")?.trim_end();
+
+        let (name, filenames) = match info
+            .strip_suffix(')')
+            .and_then(|info| info.rsplit_once(" (filenames "))
+        {
+            Some((name, filenames)) => (name, split_list(filenames)),
+            None => (info, Vec::new()),
+        };
+
+        lexers.push(LexerInfo {
+            name: name.to_owned(),
+            aliases: split_list(aliases),
+            filenames,
+            mimetypes: Vec::new(),
+        });
+    }
+
+    if lexers.is_empty() {
+        return None;
+    }
+    lexers.sort_by(|a, b| a.name.cmp(&b.name));
+    Some(lexers)
+}
+
+/// Splits e.g. `"rust, rs"` into `["rust", "rs"]`, where an empty
+/// string is an empty list.
+fn split_list(list: &str) -> Vec<String> {
+    list.split(", ")
+        .filter(|item| !item.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+/// Parses the output of `pygmentize -L lexers --json`.
+fn parse_lexers_json(output: &str) -> Option<Vec<LexerInfo>> {
+    let Value::Object(lexers) = json::parse(output)?.get("lexers")?.clone() else {
+        return None;
+    };
+
+    let strings = |lexer: &Value, key: &str| -> Option<Vec<String>> {
+        match lexer.get(key)? {
+            Value::Array(values) => values
+                .iter()
+                .map(|value| value.as_str().map(str::to_owned))
+                .collect(),
+            _ => None,
+        }
+    };
+
+    let mut lexers = lexers
+        .into_iter()
+        .map(|(name, lexer)| {
+            Some(LexerInfo {
+                aliases: strings(&lexer, "aliases")?,
+                filenames: strings(&lexer, "filenames")?,
+                mimetypes: strings(&lexer, "mimetypes")?,
+                name,
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    lexers.sort_by(|a, b| a.name.cmp(&b.name));
+    Some(lexers)
+}
@@ -76,24 +76,127 @@
     allow(missing_docs, dead_code, unused_imports, unreachable_code)
 )]
 
+#[cfg(feature = "async")]
+pub use async_highlight::highlight_async;
+pub use cache::{clear_cache, highlight_cached};
+pub use control::{highlight_controlled, Control};
+pub use corpus::{validate_corpus, CorpusIssue, CorpusIssueKind};
+pub use detect::highlight_smart;
+pub use email::highlight_email;
+pub use embedded::highlight_embedded;
+pub use file::{highlight_dir, highlight_file};
+pub use filter::Filter;
 pub use formatters::prelude::*;
+pub use frozen::FrozenFormatter;
+pub use highlighted::{highlight_detailed, highlight_html_with_css, Highlighted, HighlightedHtml};
+pub use hl_lines::HlLines;
+pub use html::{line_number_counter_defs, normalize_for_snapshot, strip_highlight, used_classes};
+pub use instance::Pygmentize;
+pub use join::join_fragments;
+pub use lang::{IntoLang, Lang};
+pub use lexer_options::{set_lexer_options, LexerOptions};
+pub use lexers::{
+    guess_lexer, guess_lexer_for_filename, highlight_named, is_valid_lexer, list_lexers,
+    register_alias, GuessMethod, LexerInfo,
+};
+pub use rust::highlight_rust;
+pub use stdio::highlight_stdio;
+#[cfg(feature = "async")]
+pub use stream::highlight_stream_async;
+pub use styles::list_styles;
+pub use swatch::{style_swatch, StyleSwatch};
+pub use temp_file::set_temp_file_threshold;
+pub use terminal::{
+    highlight_boxed, highlight_terminal_lines, print_highlighted, strip_ansi, supports_color,
+};
+pub use tokens::{token_type_histogram, tokenize, TokenType};
+pub use transform::{add_transform, clear_transforms, Transform};
+pub use truncate::set_max_line_length;
+pub use typst::TypstFormatter;
+#[cfg(unix)]
+pub use unix::{set_memory_limit, set_nice};
+pub use version::{pygmentize_version, require_min_version, version, Version};
+pub use writer::highlight_to_writer;
 
+#[cfg(feature = "async")]
+mod async_highlight;
+mod cache;
+mod control;
+mod corpus;
+mod detect;
+mod email;
+mod embedded;
+mod file;
+mod filter;
 mod formatters;
+mod frozen;
+mod highlighted;
+mod hl_lines;
+mod html;
+mod instance;
+mod join;
+mod json;
+mod lang;
+mod lexer_options;
+mod lexers;
+mod rust;
+mod stdio;
+#[cfg(feature = "async")]
+mod stream;
+mod styles;
+mod swatch;
+mod temp_file;
+mod terminal;
+mod tokens;
+mod transform;
+mod truncate;
+mod typst;
+#[cfg(unix)]
+mod unix;
+mod version;
+mod writer;
 
 use std::borrow::Cow;
 use std::error;
-use std::ffi::OsStr;
+use std::ffi::{OsStr, OsString};
 use std::fmt;
 use std::io::{self, Write};
-use std::process::{Command, ExitStatus, Stdio};
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
+use std::str;
 use std::string::FromUtf8Error;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::RwLock;
+use std::thread;
+use std::time::Duration;
+
+use crate::lexer_options::lexer_args;
+use crate::lexers::{is_valid_lexer_with, resolve_alias};
+use crate::temp_file::run_cmd_with_code;
+use crate::transform::apply_transforms;
+use crate::truncate::truncate_lines;
+
+#[cfg(windows)]
+use std::env;
+#[cfg(windows)]
+use std::path::{Path, PathBuf};
 
 #[cfg(windows)]
 use winapi_util::console::Console;
 
 static PYGMENTIZE: RwLock<Cow<'static, str>> = RwLock::new(Cow::Borrowed("pygmentize"));
 
+static FALLBACK_PYGMENTIZE: RwLock<Option<Cow<'static, str>>> = RwLock::new(None);
+
+static SPAWN_RETRIES: AtomicU32 = AtomicU32::new(1);
+
+static REJECT_EMPTY_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+static REQUIRE_LANGUAGE: AtomicBool = AtomicBool::new(false);
+
+static VALIDATE_LANGUAGE: AtomicBool = AtomicBool::new(false);
+
+const SPAWN_RETRY_BACKOFF: Duration = Duration::from_millis(10);
+
 /// Overwrite the path to the `pygmentize` binary. The default path is `"pygmentize"`.
 ///
 /// If `pygmentize` is installed in a virtual environment, within your crate directory,
@@ -107,11 +210,99 @@ pub fn set_bin_path(pygmentize: impl Into<Cow<'static, str>>) {
     *PYGMENTIZE.write().unwrap() = pygmentize.into();
 }
 
+/// Returns whether `pygmentize` can be executed, i.e. whether `pygmentize -V`
+/// succeeds using the binary set with [`set_bin_path()`], or the fallback set
+/// with [`set_fallback_bin_path()`], e.g. to show a setup message at startup,
+/// instead of failing when highlighting.
+///
+/// ```rust
+/// if !pygmentize::is_available() {
+///     eprintln!("error: pygmentize was not found, install it with `pip install Pygments`");
+/// }
+/// ```
+pub fn is_available() -> bool {
+    run_cmd_bytes(["-V"], None).is_ok()
+}
+
+/// Set the path to a `pygmentize` binary, which is used if the binary
+/// set with [`set_bin_path()`] is not found, e.g. a portable build of
+/// Pygments (such as one produced by PyInstaller) shipped alongside the
+/// application, for hosts without Pygments installed. Use `None` to
+/// remove the fallback, which is the default.
+///
+/// The crate neither bundles nor downloads Pygments, i.e. the fallback
+/// binary must be built and shipped by the application.
+///
+/// ```no_run
+/// // Prefer the system `pygmentize`, otherwise use the bundled one
+/// pygmentize::set_fallback_bin_path(Some("./vendor/pygmentize/pygmentize".into()));
+/// ```
+pub fn set_fallback_bin_path(pygmentize: Option<Cow<'static, str>>) {
+    *FALLBACK_PYGMENTIZE.write().unwrap() = pygmentize;
+}
+
+/// Overwrite how many times spawning `pygmentize` is retried, if it
+/// fails with a transient error, e.g. `EAGAIN` under heavy load.
+/// The default is `1`. Use `0` to disable retrying.
+///
+/// Only spawning is retried, i.e. neither `pygmentize` not being
+/// found nor `pygmentize` exiting with an error is retried.
+pub fn set_spawn_retries(retries: u32) {
+    SPAWN_RETRIES.store(retries, Ordering::Relaxed);
+}
+
+/// Return [`PygmentizeError::EmptyOutput`] if `pygmentize` succeeds,
+/// but outputs nothing for non-empty input, which is almost always
+/// caused by a misconfiguration, e.g. a broken formatter plugin.
+/// The default is `false`.
+///
+/// This is opt-in, as some formatters, e.g. the `null` formatter,
+/// legitimately output nothing.
+pub fn set_reject_empty_output(reject: bool) {
+    REJECT_EMPTY_OUTPUT.store(reject, Ordering::Relaxed);
+}
+
+/// Return [`PygmentizeError::LanguageRequired`] if no language is
+/// given, instead of letting `pygmentize` guess the language, which
+/// is not very reliable. The default is `false`, i.e. guessing.
+///
+/// ```rust
+/// use pygmentize::{HtmlFormatter, PygmentizeError};
+///
+/// pygmentize::set_require_language(true);
+///
+/// let res = pygmentize::highlight("fn main() {}", None, &HtmlFormatter::default());
+/// assert!(matches!(res, Err(PygmentizeError::LanguageRequired)));
+/// ```
+pub fn set_require_language(require: bool) {
+    REQUIRE_LANGUAGE.store(require, Ordering::Relaxed);
+}
+
+/// Return [`PygmentizeError::UnknownLexer`] if no lexer exists for the
+/// given language, see [`is_valid_lexer()`], before executing `pygmentize`
+/// to highlight. The default is `false`, i.e. the same error is returned
+/// after `pygmentize` fails.
+///
+/// ```rust
+/// use pygmentize::{HtmlFormatter, PygmentizeError};
+///
+/// pygmentize::set_validate_language(true);
+///
+/// let res = pygmentize::highlight("x = 1", Some("not-a-language"), &HtmlFormatter::default());
+/// assert!(matches!(res, Err(PygmentizeError::UnknownLexer(lang)) if lang == "not-a-language"));
+/// ```
+pub fn set_validate_language(validate: bool) {
+    VALIDATE_LANGUAGE.store(validate, Ordering::Relaxed);
+}
+
 /// Applies syntax highlighting to `code` written in `lang`,
 /// and outputs in the format of `F: `[`PygmentizeFormatter`].
 ///
+/// The `lang` is either a [`Lang`], an alias, e.g. `"rust"`, or
+/// `Option<&str>`, see [`IntoLang`].
 /// If `lang` is `None` then the language is guessed from `code`.
-/// Note though, that this option is not very reliable.
+/// Note though, that this option is not very reliable, see also
+/// [`set_require_language()`].
 ///
 /// See supported languages at <https://pygments.org/languages/>.
 ///
@@ -158,6 +349,213 @@ pub fn set_bin_path(pygmentize: impl Into<Cow<'static, str>>) {
 /// ```
 pub fn highlight<F>(
     code: impl AsRef<str>,
+    lang: impl IntoLang,
+    fmt: &F,
+) -> Result<String, PygmentizeError>
+where
+    F: PygmentizeFormatter,
+{
+    let lang = lang.into_lang();
+    highlight_with_lexer_options(code, lang.as_ref().map(Lang::as_alias), fmt, None)
+}
+
+/// Same as [`highlight()`], but `lexer_options` override the defaults
+/// set with [`set_lexer_options()`] for this call only, see
+/// [`LexerOptions::merged()`]. The defaults are left unchanged.
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::{HtmlFormatter, LexerOptions};
+///
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let mut lexer_options = LexerOptions::default();
+/// lexer_options.options.insert("startinline".into(), "true".into());
+///
+/// // Highlight PHP without the opening `<?php` tag
+/// let code = "echo 'Hello World';";
+/// let html = pygmentize::highlight_with_lexer_options(
+///     code,
+///     Some("php"),
+///     &HtmlFormatter::default(),
+///     Some(&lexer_options),
+/// )?;
+/// assert!(html.contains(r#"<span class="k">echo</span>"#));
+/// # Ok(())
+/// # }
+/// ```
+pub fn highlight_with_lexer_options<F>(
+    code: impl AsRef<str>,
+    lang: Option<&str>,
+    fmt: &F,
+    lexer_options: Option<&LexerOptions>,
+) -> Result<String, PygmentizeError>
+where
+    F: PygmentizeFormatter,
+{
+    highlight_with(
+        None,
+        code.as_ref(),
+        lang,
+        fmt,
+        lexer_options,
+        Control::default(),
+    )
+}
+
+/// Same as [`highlight()`], but executes the `pygmentize` binary at
+/// `bin` for this call only, instead of the one set with
+/// [`set_bin_path()`], e.g. when different parts of an application
+/// use different Pygments installations. The fallback set with
+/// [`set_fallback_bin_path()`] is not used.
+///
+/// # Example
+///
+/// ```rust
+/// use std::ffi::OsStr;
+/// use pygmentize::{HtmlFormatter, PygmentizeError};
+///
+/// # fn main() -> Result<(), PygmentizeError> {
+/// let bin = OsStr::new("pygmentize");
+/// let html = pygmentize::highlight_with_bin(bin, "fn main() {}", Some("rust"), &HtmlFormatter::default())?;
+/// println!("{html}");
+///
+/// let bin = OsStr::new("./env/bin/pygmentize-does-not-exist");
+/// let res = pygmentize::highlight_with_bin(bin, "fn main() {}", Some("rust"), &HtmlFormatter::default());
+/// assert!(matches!(res, Err(PygmentizeError::NotFound(_))));
+/// # Ok(())
+/// # }
+/// ```
+pub fn highlight_with_bin<F>(
+    bin: &OsStr,
+    code: impl AsRef<str>,
+    lang: Option<&str>,
+    fmt: &F,
+) -> Result<String, PygmentizeError>
+where
+    F: PygmentizeFormatter,
+{
+    highlight_with(
+        Some(bin),
+        code.as_ref(),
+        lang,
+        fmt,
+        None,
+        Control::default(),
+    )
+}
+
+/// Executes `bin`, or the binary set with [`set_bin_path()`] if `None`,
+/// which is killed according to `control`.
+fn highlight_with<F>(
+    bin: Option<&OsStr>,
+    code: &str,
+    lang: Option<&str>,
+    fmt: &F,
+    lexer_options: Option<&LexerOptions>,
+    control: Control<'_>,
+) -> Result<String, PygmentizeError>
+where
+    F: PygmentizeFormatter,
+{
+    let output = highlight_bytes_with(bin, code.as_bytes(), lang, fmt, lexer_options, control)?;
+    let output = String::from_utf8(output).map_err(PygmentizeError::InvalidUtf8)?;
+    let output = fmt.post_process_with(bin, code, output)?;
+    Ok(apply_transforms(output))
+}
+
+/// Same as `highlight_with()`, but neither the code nor the output
+/// are required to be UTF-8, and no post-processing is applied.
+pub(crate) fn highlight_bytes_with<F>(
+    bin: Option<&OsStr>,
+    code: &[u8],
+    lang: Option<&str>,
+    fmt: &F,
+    lexer_options: Option<&LexerOptions>,
+    control: Control<'_>,
+) -> Result<Vec<u8>, PygmentizeError>
+where
+    F: PygmentizeFormatter,
+{
+    // Code in other encodings, see `LexerOptions::input_encoding`,
+    // is passed as is, as it cannot be truncated nor validated
+    let truncated;
+    let code = match str::from_utf8(code) {
+        Ok(code) => {
+            truncated = truncate_lines(code);
+            fmt.validate_input(&truncated)?;
+            truncated.as_bytes()
+        }
+        Err(_) => code,
+    };
+
+    let args = fmt_args_with(bin, lang, fmt, lexer_options)?;
+    let output = run_cmd_with_code(bin, args.iter().map(|arg| &**arg), code, control)?;
+    check_empty_output(code, &output)?;
+    Ok(output)
+}
+
+/// Spawns `pygmentize` configured to highlight `lang` using `fmt`,
+/// with stdin, stdout, and stderr piped. The code is then written to
+/// stdin, and the output read from stdout, i.e. the caller is in full
+/// control of the I/O.
+///
+/// This is a lower-level alternative to [`highlight()`], note that
+/// neither [`PygmentizeFormatter::post_process()`] nor any
+/// [transforms](add_transform) are applied.
+///
+/// _Remember to close stdin, otherwise `pygmentize` will wait
+/// for more input indefinitely._
+///
+/// # Example
+///
+/// ```rust
+/// use std::io::{Read, Write};
+/// use pygmentize::HtmlFormatter;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut child = pygmentize::spawn(Some("rust"), &HtmlFormatter::default())?;
+///
+/// let mut stdin = child.stdin.take().unwrap();
+/// stdin.write_all(b"fn main() {}")?;
+/// // Close stdin
+/// drop(stdin);
+///
+/// let mut html = String::new();
+/// child.stdout.take().unwrap().read_to_string(&mut html)?;
+/// assert!(child.wait()?.success());
+///
+/// println!("{html}");
+/// # Ok(())
+/// # }
+/// ```
+pub fn spawn<F>(lang: Option<&str>, fmt: &F) -> Result<Child, PygmentizeError>
+where
+    F: PygmentizeFormatter,
+{
+    let args = fmt_args(lang, fmt)?;
+    spawn_cmd(args.iter().map(|arg| &**arg), true)
+}
+
+/// Same as [`highlight()`], but `code` is given as bytes, which are
+/// validated to be UTF-8, i.e. [`PygmentizeError::InvalidUtf8`] is
+/// returned if `code` is not valid UTF-8.
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::{HtmlFormatter, PygmentizeError};
+///
+/// # fn main() -> Result<(), PygmentizeError> {
+/// let code: Vec<u8> = b"fn main() {}".to_vec();
+///
+/// let html = pygmentize::highlight_utf8_bytes(&code, Some("rust"), &HtmlFormatter::default())?;
+/// println!("{html}");
+/// # Ok(())
+/// # }
+/// ```
+pub fn highlight_utf8_bytes<F>(
+    code: impl AsRef<[u8]>,
     lang: Option<&str>,
     fmt: &F,
 ) -> Result<String, PygmentizeError>
@@ -165,72 +563,264 @@ where
     F: PygmentizeFormatter,
 {
     let code = code.as_ref();
-    let opt = fmt.options_str();
-    let args = to_args(lang, F::SHORT_NAME, opt.as_deref());
-    run_cmd(args, Some(code))
+    let code = match str::from_utf8(code) {
+        Ok(code) => code,
+        // `FromUtf8Error` requires an owned `Vec<u8>`,
+        // so only allocate if `code` is invalid
+        Err(_) => {
+            let err = String::from_utf8(code.to_vec()).unwrap_err();
+            return Err(PygmentizeError::InvalidUtf8(err));
+        }
+    };
+    highlight(code, lang, fmt)
 }
 
-fn to_args<'a>(
+/// Same as [`highlight()`], but the output is returned as bytes, i.e. it
+/// is not required to be UTF-8, e.g. for binary formats such as the
+/// images output by [`ImageFormatter`], or other encodings, see
+/// [`LexerOptions::output_encoding`].
+///
+/// Likewise `code` is not required to be UTF-8, see
+/// [`LexerOptions::input_encoding`].
+///
+/// As the output is not a `String`, neither
+/// [`PygmentizeFormatter::post_process()`] nor any
+/// [transforms](add_transform) are applied.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use pygmentize::ImageFormatter;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let png = pygmentize::highlight_bytes("fn main() {}", Some("rust"), &ImageFormatter::default())?;
+/// std::fs::write("main.png", png)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn highlight_bytes<F>(
+    code: impl AsRef<[u8]>,
+    lang: Option<&str>,
+    fmt: &F,
+) -> Result<Vec<u8>, PygmentizeError>
+where
+    F: PygmentizeFormatter,
+{
+    highlight_bytes_with(None, code.as_ref(), lang, fmt, None, Control::default())
+}
+
+/// Validates `fmt` and returns the arguments
+/// for highlighting `lang` using `fmt`.
+fn fmt_args<'a, F>(lang: Option<&'a str>, fmt: &'a F) -> Result<Vec<Cow<'a, str>>, PygmentizeError>
+where
+    F: PygmentizeFormatter,
+{
+    fmt_args_with(None, lang, fmt, None)
+}
+
+/// Same as `fmt_args()`, but with `lexer_options` overriding
+/// the defaults set with [`set_lexer_options()`].
+fn fmt_args_with<'a, F>(
+    bin: Option<&OsStr>,
     lang: Option<&'a str>,
+    fmt: &'a F,
+    lexer_options: Option<&LexerOptions>,
+) -> Result<Vec<Cow<'a, str>>, PygmentizeError>
+where
+    F: PygmentizeFormatter,
+{
+    fmt.validate()?;
+
+    if lang.is_none() && REQUIRE_LANGUAGE.load(Ordering::Relaxed) {
+        return Err(PygmentizeError::LanguageRequired);
+    }
+
+    if let Some(lang) = lang {
+        if VALIDATE_LANGUAGE.load(Ordering::Relaxed) && !is_valid_lexer_with(bin, lang)? {
+            return Err(PygmentizeError::UnknownLexer(lang.to_owned()));
+        }
+    }
+
+    // Lexer filters first, such that e.g. tokens are merged last
+    let (lexer_options, mut filters) = lexer_args(lexer_options)?;
+    filters.extend_from_slice(&fmt.filters());
+
+    // Lexer options first, such that the formatter's options take precedence
+    let options = match (lexer_options, fmt.options_str()) {
+        (Some(lexer), Some(fmt)) => Some(Cow::Owned(format!("{lexer},{fmt}"))),
+        (Some(lexer), None) => Some(Cow::Owned(lexer)),
+        (None, fmt) => fmt,
+    };
+
+    Ok(to_args(
+        lang.map(resolve_alias),
+        F::SHORT_NAME,
+        options,
+        &fmt.verbatim_options(),
+        &filters,
+    ))
+}
+
+fn to_args<'a>(
+    lang: Option<Cow<'a, str>>,
     fmt_name: &'a str,
-    options: Option<&'a str>,
-) -> impl IntoIterator<Item = &'a str> + 'a {
-    let mut args = [""; 6];
-    args[0] = "-f";
-    args[1] = fmt_name;
-    let mut argi = 2;
+    options: Option<Cow<'a, str>>,
+    verbatim_options: &[(&str, &str)],
+    filters: &[Filter],
+) -> Vec<Cow<'a, str>> {
+    let mut args = Vec::with_capacity(6 + ((verbatim_options.len() + filters.len()) * 2));
+    args.push(Cow::Borrowed("-f"));
+    args.push(Cow::Borrowed(fmt_name));
 
     if let Some(lang) = lang {
-        args[argi] = "-l";
-        args[argi + 1] = lang;
-        argi += 2;
+        args.push(Cow::Borrowed("-l"));
+        args.push(lang);
     } else {
-        args[argi] = "-g";
-        argi += 1;
+        args.push(Cow::Borrowed("-g"));
     }
 
     if let Some(opt) = options {
-        args[argi] = "-O";
-        args[argi + 1] = opt.as_ref();
-        argi += 2;
+        args.push(Cow::Borrowed("-O"));
+        args.push(opt);
+    }
+
+    for (key, value) in verbatim_options {
+        args.push(Cow::Borrowed("-P"));
+        args.push(Cow::Owned(format!("{key}={value}")));
+    }
+
+    for filter in filters {
+        args.push(Cow::Borrowed("-F"));
+        args.push(Cow::Owned(filter.to_arg()));
+    }
+
+    args
+}
+
+/// See [`set_reject_empty_output()`].
+fn check_empty_output(code: &[u8], output: &[u8]) -> Result<(), PygmentizeError> {
+    if REJECT_EMPTY_OUTPUT.load(Ordering::Relaxed) && !code.is_empty() && output.is_empty() {
+        return Err(PygmentizeError::EmptyOutput);
+    }
+    Ok(())
+}
+
+/// Returns the CSS rules for `style`, i.e. the stylesheet for the output
+/// of [`HtmlFormatter`], where the token rules are prefixed with
+/// `selector`, e.g. `Some(".highlight")`. If `None`, the token rules
+/// are not prefixed, e.g. `.k { ... }`.
+///
+/// See <https://pygments.org/styles/> for available styles.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let css = pygmentize::get_style_defs("dracula", Some(".highlight"))?;
+/// assert!(css.contains(".highlight .k {"));
+///
+/// let css = pygmentize::get_style_defs("dracula", None)?;
+/// assert!(css.contains("\n.k {"));
+/// # Ok(())
+/// # }
+/// ```
+pub fn get_style_defs(style: &str, selector: Option<&str>) -> Result<String, PygmentizeError> {
+    get_style_defs_with(None, style, selector)
+}
+
+/// Same as [`get_style_defs()`], but for `bin` instead of the global binary, if given.
+pub(crate) fn get_style_defs_with(
+    bin: Option<&OsStr>,
+    style: &str,
+    selector: Option<&str>,
+) -> Result<String, PygmentizeError> {
+    let mut args = vec!["-S", style, "-f", "html"];
+    if let Some(selector) = selector {
+        args.extend(["-a", selector]);
     }
+    let output = run_cmd_with_bin(bin, args, None)?;
+    String::from_utf8(output).map_err(PygmentizeError::InvalidUtf8)
+}
+
+fn run_cmd<I, S>(args: I, stdin: Option<&[u8]>) -> Result<String, PygmentizeError>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let output = run_cmd_bytes(args, stdin)?;
+    String::from_utf8(output).map_err(PygmentizeError::InvalidUtf8)
+}
 
-    debug_assert!(argi <= args.len());
+/// Same as `run_cmd()`, but the output is returned as bytes,
+/// i.e. it is not validated as UTF-8.
+fn run_cmd_bytes<I, S>(args: I, stdin: Option<&[u8]>) -> Result<Vec<u8>, PygmentizeError>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    run_cmd_with_bin(None, args, stdin)
+}
 
-    args.into_iter().filter(|arg| !arg.is_empty())
+/// Returns the path to the `pygmentize` binary, i.e. `bin` if given,
+/// otherwise the global path, e.g. for caching the results per binary.
+fn bin_key(bin: Option<&OsStr>) -> OsString {
+    match bin {
+        Some(bin) => bin.to_os_string(),
+        None => OsString::from(&**PYGMENTIZE.read().unwrap()),
+    }
 }
 
-fn run_cmd<I, S>(args: I, stdin: Option<&str>) -> Result<String, PygmentizeError>
+/// Same as `run_cmd_bytes()`, but executes `bin`, or
+/// the binary set with [`set_bin_path()`] if `None`.
+fn run_cmd_with_bin<I, S>(
+    bin: Option<&OsStr>,
+    args: I,
+    stdin: Option<&[u8]>,
+) -> Result<Vec<u8>, PygmentizeError>
 where
     I: IntoIterator<Item = S>,
     S: AsRef<OsStr>,
 {
-    let mut child = Command::new(PYGMENTIZE.read().unwrap().as_ref())
-        .args(args)
-        .stdin(if stdin.is_some() {
-            Stdio::piped()
-        } else {
-            Stdio::null()
-        })
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .map_err(|err| match err {
-            _ if err.kind() == io::ErrorKind::NotFound => PygmentizeError::NotFound(err),
-            _ => PygmentizeError::Process(err),
-        })?;
-
-    if let Some(data) = stdin {
-        let mut stdin = child.stdin.take().expect("expected stdin");
-        stdin
-            .write_all(data.as_bytes())
-            .map_err(PygmentizeError::Process)?;
-        stdin.flush().map_err(PygmentizeError::Process)?;
-        // Calling `wait_with_output()` closes stdin
+    let mut child = spawn_with(bin, args, stdin.is_some(), |mut cmd| cmd.spawn())?;
+
+    // stdin is written on a separate thread, while stdout and stderr are
+    // read, as otherwise both `pygmentize` and this thread could block on
+    // a full pipe for large inputs, i.e. `pygmentize` writing stdout and
+    // this thread writing stdin
+    let (output, stdin_res) = thread::scope(|s| {
+        let stdin_thread = stdin.map(|data| {
+            let mut stdin = child.stdin.take().expect("expected stdin");
+            // Dropping stdin closes it
+            s.spawn(move || stdin.write_all(data).and_then(|_| stdin.flush()))
+        });
+
+        // The child is always waited for, also if writing stdin
+        // fails, such that it is not left as a zombie
+        let output = child.wait_with_output();
+        let stdin_res = stdin_thread.map(|thread| thread.join().expect("stdin thread panicked"));
+        (output, stdin_res)
+    });
+
+    let output = output.map_err(PygmentizeError::Process)?;
+
+    match stdin_res {
+        // `pygmentize` exiting before reading all of stdin is
+        // reported through its exit status instead
+        Some(Err(err)) if err.kind() == io::ErrorKind::BrokenPipe => {}
+        Some(Err(err)) if output.status.success() => return Err(PygmentizeError::Process(err)),
+        _ => {}
     }
 
-    let output = child.wait_with_output().map_err(PygmentizeError::Process)?;
+    output_to_bytes(output)
+}
+
+fn output_to_string(output: Output) -> Result<String, PygmentizeError> {
+    let stdout = output_to_bytes(output)?;
+    String::from_utf8(stdout).map_err(PygmentizeError::InvalidUtf8)
+}
 
+/// Returns stdout, or [`PygmentizeError::Pygmentize`] if `pygmentize` failed.
+fn output_to_bytes(output: Output) -> Result<Vec<u8>, PygmentizeError> {
     // Executing `pygmentize` causes `ENABLE_VIRTUAL_TERMINAL_PROCESSING` to get turned off
     #[cfg(windows)]
     enable_virtual_terminal_processing();
@@ -240,10 +830,173 @@ where
             Ok(stderr) => stderr,
             Err(err) => String::from_utf8_lossy(err.as_bytes()).into_owned(),
         };
-        return Err(PygmentizeError::Pygmentize(output.status, stderr));
+        return Err(exit_error(output.status, stderr));
+    }
+
+    Ok(output.stdout)
+}
+
+/// Returns the error for `pygmentize` exiting with `status`, i.e.
+/// [`PygmentizeError::UnknownLexer`] or [`PygmentizeError::UnknownFormatter`]
+/// if `stderr` is recognized, otherwise [`PygmentizeError::Pygmentize`].
+pub(crate) fn exit_error(status: ExitStatus, stderr: String) -> PygmentizeError {
+    // e.g. `Error: no lexer for alias 'foo' found`
+    let quoted = |prefix: &str, suffix: &str| {
+        let (_, rest) = stderr.split_once(prefix)?;
+        let (name, _) = rest.split_once(suffix)?;
+        Some(name.to_owned())
+    };
+
+    if let Some(lang) = quoted("no lexer for alias '", "' found") {
+        PygmentizeError::UnknownLexer(lang)
+    } else if let Some(fmt) = quoted("no formatter found for name '", "'") {
+        PygmentizeError::UnknownFormatter(fmt)
+    } else {
+        PygmentizeError::Pygmentize(status, stderr)
+    }
+}
+
+fn spawn_cmd<I, S>(args: I, piped_stdin: bool) -> Result<Child, PygmentizeError>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    spawn_with(None, args, piped_stdin, |mut cmd| cmd.spawn())
+}
+
+/// Configures the command for `args`, and spawns it using `spawn`, e.g.
+/// `Command::spawn()`, retrying on transient errors.
+///
+/// If `bin` is `None`, the binary set with [`set_bin_path()`] is used,
+/// falling back to [`set_fallback_bin_path()`] if it is not found.
+fn spawn_with<I, S, T>(
+    bin: Option<&OsStr>,
+    args: I,
+    piped_stdin: bool,
+    spawn: impl Fn(Command) -> io::Result<T>,
+) -> Result<T, PygmentizeError>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let args = args.into_iter().collect::<Vec<_>>();
+
+    if let Some(bin) = bin {
+        return spawn_bin(bin, &args, piped_stdin, &spawn);
     }
 
-    String::from_utf8(output.stdout).map_err(PygmentizeError::InvalidUtf8)
+    let bin = PYGMENTIZE.read().unwrap().clone();
+    match spawn_bin(OsStr::new(&*bin), &args, piped_stdin, &spawn) {
+        Err(PygmentizeError::NotFound(err)) => {
+            let fallback = FALLBACK_PYGMENTIZE.read().unwrap().clone();
+            match fallback {
+                Some(fallback) => spawn_bin(OsStr::new(&*fallback), &args, piped_stdin, &spawn),
+                None => Err(PygmentizeError::NotFound(err)),
+            }
+        }
+        res => res,
+    }
+}
+
+fn spawn_bin<S, T>(
+    bin: &OsStr,
+    args: &[S],
+    piped_stdin: bool,
+    spawn: &impl Fn(Command) -> io::Result<T>,
+) -> Result<T, PygmentizeError>
+where
+    S: AsRef<OsStr>,
+{
+    let retries = SPAWN_RETRIES.load(Ordering::Relaxed);
+
+    let mut attempt = 0;
+    loop {
+        let mut cmd = new_command(bin);
+        cmd.args(args)
+            .stdin(if piped_stdin {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        match spawn(cmd) {
+            Ok(child) => return Ok(child),
+            Err(err) if (attempt < retries) && is_transient(&err) => {
+                attempt += 1;
+                thread::sleep(SPAWN_RETRY_BACKOFF * attempt);
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Err(PygmentizeError::NotFound(err));
+            }
+            Err(err) => return Err(PygmentizeError::Process(err)),
+        }
+    }
+}
+
+/// Errors which can occur when spawning under heavy load,
+/// e.g. `EAGAIN`, and are likely to succeed if retried.
+fn is_transient(err: &io::Error) -> bool {
+    matches!(
+        err.kind(),
+        io::ErrorKind::WouldBlock
+            | io::ErrorKind::Interrupted
+            | io::ErrorKind::OutOfMemory
+            | io::ErrorKind::ResourceBusy
+    )
+}
+
+fn new_command(bin: &OsStr) -> Command {
+    #[cfg_attr(not(unix), allow(unused_mut))]
+    let mut cmd = {
+        #[cfg(windows)]
+        match resolve_windows_bin(bin) {
+            Some(path) => Command::new(path),
+            None => Command::new(bin),
+        }
+        #[cfg(not(windows))]
+        Command::new(bin)
+    };
+
+    #[cfg(unix)]
+    unix::configure_command(&mut cmd);
+
+    cmd
+}
+
+/// pip on Windows sometimes installs `pygmentize` as a `.bat` or `.cmd`
+/// shim, which `CreateProcess` does not find without the extension.
+/// Resolve the binary by trying the `PATHEXT` extensions. Batch files
+/// are then executed by `std::process::Command` through `cmd.exe`
+/// (including escaping of the arguments), so resolving the full path
+/// is enough.
+#[cfg(windows)]
+fn resolve_windows_bin(bin: &OsStr) -> Option<PathBuf> {
+    let bin = Path::new(bin);
+    if bin.extension().is_some() {
+        return None;
+    }
+
+    let path_ext = env::var("PATHEXT").unwrap_or_else(|_| String::from(".COM;.EXE;.BAT;.CMD"));
+    let exts = path_ext
+        .split(';')
+        .map(|ext| ext.trim().trim_start_matches('.'))
+        .filter(|ext| !ext.is_empty())
+        .collect::<Vec<_>>();
+
+    let dirs = if bin.components().count() > 1 {
+        vec![PathBuf::new()]
+    } else {
+        env::split_paths(&env::var_os("PATH")?).collect()
+    };
+
+    dirs.iter().find_map(|dir| {
+        let bin = dir.join(bin);
+        exts.iter()
+            .map(|ext| bin.with_extension(ext))
+            .find(|path| path.is_file())
+    })
 }
 
 #[cfg(windows)]
@@ -272,6 +1025,38 @@ pub enum PygmentizeError {
     InvalidUtf8(FromUtf8Error),
     /// The pygmentize binary returned an error.
     Pygmentize(ExitStatus, String),
+    /// The output of `pygmentize -V` could not be parsed.
+    InvalidVersion(String),
+    /// The input is already highlighted, see
+    /// [`HtmlFormatter::reject_highlighted`].
+    AlreadyHighlighted,
+    /// Reading files or directories, reading stdin or writing
+    /// stdout, writing to the writer given to [`highlight_to_writer()`],
+    /// or writing the file for [`set_temp_file_threshold()`], failed.
+    Io(io::Error),
+    /// A formatter option has an invalid value,
+    /// given as the name of the option and the reason.
+    InvalidOption(&'static str, String),
+    /// `pygmentize` was killed, as it exceeded the timeout.
+    Timeout,
+    /// `pygmentize` was killed, as it was cancelled.
+    Cancelled,
+    /// `pygmentize` succeeded, but output nothing for non-empty
+    /// input, see [`set_reject_empty_output()`].
+    EmptyOutput,
+    /// No language was given, see [`set_require_language()`].
+    LanguageRequired,
+    /// The installed Pygments is older than required, given as the
+    /// found and required version, see [`require_min_version()`].
+    UnsupportedVersion(Version, Version),
+    /// The output of `pygmentize` could not be parsed,
+    /// e.g. the output of `pygmentize -L lexers` or `pygmentize -L styles`.
+    InvalidOutput(String),
+    /// No lexer exists for the language, either reported by `pygmentize`
+    /// or checked beforehand, see [`set_validate_language()`].
+    UnknownLexer(String),
+    /// No formatter exists for the name, as reported by `pygmentize`.
+    UnknownFormatter(String),
 }
 
 impl error::Error for PygmentizeError {
@@ -281,6 +1066,18 @@ impl error::Error for PygmentizeError {
             Self::NotFound(err) => Some(err),
             Self::InvalidUtf8(err) => Some(err),
             Self::Pygmentize(_, _) => None,
+            Self::InvalidVersion(_) => None,
+            Self::AlreadyHighlighted => None,
+            Self::Io(err) => Some(err),
+            Self::InvalidOption(_, _) => None,
+            Self::Timeout => None,
+            Self::Cancelled => None,
+            Self::EmptyOutput => None,
+            Self::LanguageRequired => None,
+            Self::UnsupportedVersion(_, _) => None,
+            Self::InvalidOutput(_) => None,
+            Self::UnknownLexer(_) => None,
+            Self::UnknownFormatter(_) => None,
         }
     }
 }
@@ -296,6 +1093,28 @@ impl fmt::Display for PygmentizeError {
             Self::Pygmentize(status, stderr) => {
                 write!(f, "pygmentize exited with {status}: {stderr}")
             }
+            Self::InvalidVersion(output) => {
+                write!(f, "unable to parse pygmentize version: {output}")
+            }
+            Self::AlreadyHighlighted => {
+                write!(f, "input is already highlighted")
+            }
+            Self::Io(err) => err.fmt(f),
+            Self::InvalidOption(name, reason) => {
+                write!(f, "invalid option `{name}`: {reason}")
+            }
+            Self::Timeout => write!(f, "pygmentize timed out"),
+            Self::Cancelled => write!(f, "pygmentize was cancelled"),
+            Self::EmptyOutput => write!(f, "pygmentize produced no output"),
+            Self::LanguageRequired => write!(f, "no language was given"),
+            Self::UnsupportedVersion(found, required) => {
+                write!(f, "Pygments {required} or newer is required, found {found}")
+            }
+            Self::InvalidOutput(output) => {
+                write!(f, "unable to parse pygmentize output: {output}")
+            }
+            Self::UnknownLexer(lang) => write!(f, "unknown lexer `{lang}`"),
+            Self::UnknownFormatter(fmt) => write!(f, "unknown formatter `{fmt}`"),
         }
     }
 }
@@ -0,0 +1,44 @@
+use std::collections::BTreeMap;
+
+use crate::{highlight_with_lexer_options, LexerOptions, PygmentizeError, PygmentizeFormatter};
+
+/// The lexer options used by [`highlight_rust()`], i.e. the indentation
+/// of `rustfmt`, and leading and trailing newlines are preserved.
+const RUST_LEXER_OPTIONS: LexerOptions = LexerOptions {
+    strip_newlines: Some(false),
+    strip_all: None,
+    ensure_newline: None,
+    tab_size: Some(4),
+    input_encoding: None,
+    output_encoding: None,
+    filters: Vec::new(),
+    options: BTreeMap::new(),
+};
+
+/// Applies syntax highlighting to Rust `code`, e.g. generated by
+/// `quote!` and `prettyplease`, using the Rust lexer.
+///
+/// Unlike [`highlight()`](crate::highlight), leading and trailing newlines
+/// are not stripped, such that e.g. `rustfmt`'s output is preserved exactly,
+/// and tabs are expanded to 4 spaces, like `rustfmt` indents. These override
+/// the defaults set with [`set_lexer_options()`](crate::set_lexer_options).
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::HtmlFormatter;
+///
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let code = "fn main() {\n\tprintln!(\"Hello World\");\n}\n\n";
+/// let html = pygmentize::highlight_rust(code, &HtmlFormatter::default())?;
+/// assert!(html.contains(r#"<span class="w">    </span><span class="fm">println!</span>"#));
+/// assert!(html.ends_with("}</span>\n\n</pre></div>\n"));
+/// # Ok(())
+/// # }
+/// ```
+pub fn highlight_rust<F>(code: impl AsRef<str>, fmt: &F) -> Result<String, PygmentizeError>
+where
+    F: PygmentizeFormatter,
+{
+    highlight_with_lexer_options(code, Some("rust"), fmt, Some(&RUST_LEXER_OPTIONS))
+}
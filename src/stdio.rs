@@ -0,0 +1,41 @@
+use std::io::{self, Read, Write};
+
+use crate::{highlight, PygmentizeError, PygmentizeFormatter};
+
+/// Reads all of stdin, applies syntax highlighting to it,
+/// and writes the result to stdout.
+///
+/// If stdout is closed before all of the output is written, e.g. when
+/// piping to `head` or a pager that is quit early, then the remaining
+/// output is discarded and `Ok(())` is returned.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use pygmentize::TerminalFormatter;
+///
+/// fn main() -> Result<(), pygmentize::PygmentizeError> {
+///     pygmentize::highlight_stdio(Some("rust"), &TerminalFormatter::default())
+/// }
+/// ```
+pub fn highlight_stdio<F>(lang: Option<&str>, fmt: &F) -> Result<(), PygmentizeError>
+where
+    F: PygmentizeFormatter,
+{
+    let mut code = String::new();
+    io::stdin()
+        .lock()
+        .read_to_string(&mut code)
+        .map_err(PygmentizeError::Io)?;
+
+    let output = highlight(code, lang, fmt)?;
+
+    let mut stdout = io::stdout().lock();
+    match stdout
+        .write_all(output.as_bytes())
+        .and_then(|_| stdout.flush())
+    {
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        res => res.map_err(PygmentizeError::Io),
+    }
+}
@@ -0,0 +1,145 @@
+use std::borrow::Cow;
+use std::process::Output;
+
+use bytes::Bytes;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::process::Command;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::Stream;
+
+use crate::truncate::truncate_lines;
+use crate::{fmt_args, output_to_string, spawn_with, PygmentizeError, PygmentizeFormatter};
+
+/// Size of the chunks read from stdout.
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// Applies syntax highlighting to `code` written in `lang`, and returns a
+/// stream of the output of `pygmentize`, which yields chunks of stdout as
+/// they are output, i.e. before `pygmentize` has finished.
+///
+/// _This requires the `async` feature, and must be called
+/// from within a Tokio runtime._
+///
+/// Neither [`PygmentizeFormatter::post_process()`] nor any
+/// [transforms](crate::add_transform) are applied, as they
+/// require the whole output.
+///
+/// If `pygmentize` fails, then the stream yields an error as its last
+/// item, possibly after having yielded some output already, i.e. only
+/// the output before the error is valid, and e.g. an HTTP response
+/// should be aborted. If the stream is dropped, then `pygmentize`
+/// is killed.
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::HtmlFormatter;
+/// use tokio_stream::StreamExt;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let mut stream = pygmentize::highlight_stream_async("fn main() {}", Some("rust"), &HtmlFormatter::default());
+///
+/// let mut html = Vec::new();
+/// while let Some(chunk) = stream.next().await {
+///     html.extend_from_slice(&chunk?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn highlight_stream_async<F>(
+    code: impl Into<String>,
+    lang: Option<&str>,
+    fmt: &F,
+) -> impl Stream<Item = Result<Bytes, PygmentizeError>>
+where
+    F: PygmentizeFormatter,
+{
+    let mut code = code.into();
+    if let Cow::Owned(truncated) = truncate_lines(&code) {
+        code = truncated;
+    }
+    let (tx, rx) = mpsc::channel(4);
+
+    let res = fmt.validate_input(&code).and_then(|_| {
+        let args = fmt_args(lang, fmt)?;
+        spawn_with(None, args.iter().map(|arg| &**arg), true, |cmd| {
+            Command::from(cmd).kill_on_drop(true).spawn()
+        })
+    });
+
+    match res {
+        Ok(child) => {
+            tokio::spawn(run(child, code, tx));
+        }
+        Err(err) => {
+            // The channel is empty, so this cannot fail
+            let _ = tx.try_send(Err(err));
+        }
+    }
+
+    ReceiverStream::new(rx)
+}
+
+async fn run(
+    mut child: tokio::process::Child,
+    code: String,
+    tx: mpsc::Sender<Result<Bytes, PygmentizeError>>,
+) {
+    let mut stdin = child.stdin.take().expect("expected stdin");
+    let mut stdout = child.stdout.take().expect("expected stdout");
+    let mut stderr = child.stderr.take().expect("expected stderr");
+
+    // Written and read concurrently, such that `pygmentize`
+    // does not block on a full pipe
+    let stdin = tokio::spawn(async move {
+        let res = stdin.write_all(code.as_bytes()).await;
+        drop(stdin);
+        res
+    });
+    let stderr = tokio::spawn(async move {
+        let mut buf = Vec::new();
+        stderr.read_to_end(&mut buf).await.map(|_| buf)
+    });
+
+    loop {
+        let mut buf = vec![0; CHUNK_SIZE];
+        match stdout.read(&mut buf).await {
+            Ok(0) => break,
+            Ok(n) => {
+                buf.truncate(n);
+                // The receiver was dropped, i.e. `pygmentize` is killed on drop
+                if tx.send(Ok(Bytes::from(buf))).await.is_err() {
+                    return;
+                }
+            }
+            Err(err) => {
+                let _ = tx.send(Err(PygmentizeError::Process(err))).await;
+                return;
+            }
+        }
+    }
+
+    let status = match child.wait().await {
+        Ok(status) => status,
+        Err(err) => {
+            let _ = tx.send(Err(PygmentizeError::Process(err))).await;
+            return;
+        }
+    };
+    let stderr = stderr.await.ok().and_then(Result::ok).unwrap_or_default();
+
+    // `pygmentize` exiting before reading all of stdin
+    // is reported through its exit status instead
+    let _ = stdin.await;
+
+    // Reuse the error mapping, the output was already sent
+    if let Err(err) = output_to_string(Output {
+        status,
+        stdout: Vec::new(),
+        stderr,
+    }) {
+        let _ = tx.send(Err(err)).await;
+    }
+}
@@ -0,0 +1,45 @@
+use crate::{run_cmd, PygmentizeError};
+
+/// Returns the names of all styles supported by the installed Pygments,
+/// sorted by name, e.g. for offering a style picker. The styles differ
+/// between Pygments versions, so prefer this over hardcoding them.
+///
+/// Returns [`PygmentizeError::InvalidOutput`] if the
+/// output of `pygmentize -L styles` cannot be parsed.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let styles = pygmentize::list_styles()?;
+/// assert!(styles.iter().any(|style| style == "default"));
+/// assert!(styles.iter().any(|style| style == "monokai"));
+/// # Ok(())
+/// # }
+/// ```
+pub fn list_styles() -> Result<Vec<String>, PygmentizeError> {
+    let output = run_cmd(["-L", "styles"], None)?;
+    parse_styles(&output).ok_or(PygmentizeError::InvalidOutput(output))
+}
+
+/// Parses the output of `pygmentize -L styles`, where each style is
+/// listed as its name followed by an indented line of its description,
+/// which can be empty, e.g.:
+///
+/// ```text
+/// * monokai:
+///     This style mimics the Monokai color scheme.
+/// ```
+fn parse_styles(output: &str) -> Option<Vec<String>> {
+    let mut names = output
+        .lines()
+        .filter_map(|line| line.strip_prefix("* ")?.strip_suffix(':'))
+        .map(str::to_owned)
+        .collect::<Vec<_>>();
+
+    if names.is_empty() {
+        return None;
+    }
+    names.sort();
+    Some(names)
+}
@@ -0,0 +1,68 @@
+use crate::{get_style_defs, PygmentizeError};
+
+const SELECTOR: &str = ".highlight";
+
+/// The key colors of a style, e.g. for previewing styles in
+/// a theme picker, without highlighting any code, see
+/// [`style_swatch()`].
+///
+/// The colors are as given by the style, e.g. `#f8f8f8`. Colors the
+/// style does not specify are `None`, e.g. many light styles do not
+/// specify a `foreground` color, i.e. the inherited color is used.
+#[derive(Clone, PartialEq, Eq, Hash, Default, Debug)]
+pub struct StyleSwatch {
+    pub background: Option<String>,
+    /// The default text color.
+    pub foreground: Option<String>,
+    pub keyword: Option<String>,
+    pub string: Option<String>,
+    pub number: Option<String>,
+    pub comment: Option<String>,
+    pub function: Option<String>,
+    pub operator: Option<String>,
+}
+
+/// Returns the key colors of `style`, see [`StyleSwatch`].
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let swatch = pygmentize::style_swatch("monokai")?;
+/// assert_eq!(swatch.background.as_deref(), Some("#272822"));
+/// assert_eq!(swatch.keyword.as_deref(), Some("#66D9EF"));
+/// # Ok(())
+/// # }
+/// ```
+pub fn style_swatch(style: &str) -> Result<StyleSwatch, PygmentizeError> {
+    let css = get_style_defs(style, Some(SELECTOR))?;
+
+    Ok(StyleSwatch {
+        background: property(&css, "", "background"),
+        foreground: property(&css, "", "color"),
+        keyword: property(&css, ".k", "color"),
+        string: property(&css, ".s", "color"),
+        number: property(&css, ".m", "color"),
+        comment: property(&css, ".c", "color"),
+        function: property(&css, ".nf", "color"),
+        operator: property(&css, ".o", "color"),
+    })
+}
+
+/// Returns the value of `name` in the rule for the token `class`,
+/// e.g. `.highlight .k { color: #008000; font-weight: bold }`,
+/// where an empty `class` is the rule for the wrapper itself.
+fn property(css: &str, class: &str, name: &str) -> Option<String> {
+    let selector = if class.is_empty() {
+        format!("{SELECTOR} {{")
+    } else {
+        format!("{SELECTOR} {class} {{")
+    };
+
+    let line = css.lines().find(|line| line.starts_with(&selector))?;
+    let (body, _) = line[selector.len()..].split_once('}')?;
+    body.split(';')
+        .filter_map(|decl| decl.split_once(':'))
+        .find(|(key, _)| key.trim() == name)
+        .map(|(_, value)| value.trim().to_owned())
+}
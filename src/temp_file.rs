@@ -0,0 +1,130 @@
+use std::env;
+use std::ffi::{OsStr, OsString};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::RwLock;
+
+use crate::control::run_cmd_controlled;
+use crate::{run_cmd_with_bin, Control, PygmentizeError};
+
+static TEMP_FILE_THRESHOLD: RwLock<Option<usize>> = RwLock::new(None);
+
+/// Pass code of at least `threshold` bytes to `pygmentize` through a
+/// temporary file, instead of writing it to stdin. For large inputs,
+/// this can be faster than writing through a pipe on some platforms.
+///
+/// The file is created in [`std::env::temp_dir()`], and is removed
+/// after `pygmentize` exits, including when highlighting fails. On Unix,
+/// the file is only readable and writable by the current user.
+/// `None` (the default) always uses stdin.
+///
+/// The threshold is honoured by [`highlight()`](crate::highlight) and the
+/// functions built on it, but not by
+/// [`highlight_to_writer()`](crate::highlight_to_writer),
+/// `highlight_async()`, and `highlight_stream_async()`, which always
+/// write the code to stdin.
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::HtmlFormatter;
+///
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// pygmentize::set_temp_file_threshold(Some(1024 * 1024));
+///
+/// let code = "let x = 1;\n".repeat(200_000);
+/// let html = pygmentize::highlight(code, Some("rust"), &HtmlFormatter::default())?;
+/// println!("{}", html.len());
+/// # pygmentize::set_temp_file_threshold(None);
+/// # Ok(())
+/// # }
+/// ```
+pub fn set_temp_file_threshold(threshold: Option<usize>) {
+    *TEMP_FILE_THRESHOLD.write().unwrap() = threshold;
+}
+
+/// Same as `run_cmd_with_bin(bin, args, Some(code))`, but `code` is passed
+/// through a temporary file, if it exceeds the [`set_temp_file_threshold()`].
+///
+/// If `control` has a timeout or cancel flag, then `run_cmd_controlled()`
+/// is used instead.
+pub(crate) fn run_cmd_with_code<I, S>(
+    bin: Option<&OsStr>,
+    args: I,
+    code: &[u8],
+    control: Control<'_>,
+) -> Result<Vec<u8>, PygmentizeError>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let run = |args: Vec<OsString>, stdin: Option<&[u8]>| {
+        if control.is_active() {
+            run_cmd_controlled(bin, args, stdin, control)
+        } else {
+            run_cmd_with_bin(bin, args, stdin)
+        }
+    };
+
+    let threshold = *TEMP_FILE_THRESHOLD.read().unwrap();
+    if threshold.is_none_or(|threshold| code.len() < threshold) {
+        let args = args
+            .into_iter()
+            .map(|arg| arg.as_ref().to_owned())
+            .collect();
+        return run(args, Some(code));
+    }
+
+    let file = TempFile::new(code).map_err(PygmentizeError::Io)?;
+    let args = args
+        .into_iter()
+        .map(|arg| arg.as_ref().to_owned())
+        .chain([file.path.clone().into_os_string()]);
+    run(args.collect(), None)
+}
+
+/// A file in the temp dir, which is removed when dropped.
+struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    fn new(contents: &[u8]) -> io::Result<Self> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let (path, mut file) = loop {
+            let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+            // No extension, such that `-g` guesses from the contents
+            let path = env::temp_dir().join(format!("pygmentize-{}-{n}", process::id()));
+
+            let mut options = OpenOptions::new();
+            options.write(true).create_new(true);
+            // The code is not necessarily meant to be readable by other users
+            #[cfg(unix)]
+            std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+            match options.open(&path) {
+                Ok(file) => break (path, file),
+                // Left over by a previous process with the same id
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {}
+                Err(err) => return Err(err),
+            }
+        };
+
+        // Removes the file if writing fails
+        let temp = Self { path };
+        file.write_all(contents)?;
+        file.flush()?;
+        Ok(temp)
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        // Errors are ignored, as there is nothing to do about them
+        let _ = fs::remove_file(&self.path);
+    }
+}
@@ -0,0 +1,317 @@
+use std::env;
+use std::io::{self, IsTerminal, Write};
+
+use unicode_width::UnicodeWidthChar;
+
+use crate::{highlight, PygmentizeError, PygmentizeFormatter};
+use crate::{Terminal256Formatter, TerminalFormatter, TerminalTrueColorFormatter};
+
+/// Applies syntax highlighting to `code` written in `lang`,
+/// and prints the result to stdout.
+///
+/// The terminal formatter is selected based on the detected color
+/// support of the terminal, see [`supports_color()`]. If colors are
+/// supported, then [`TerminalTrueColorFormatter`] if
+/// `$COLORTERM` is `truecolor` or `24bit`, [`Terminal256Formatter`]
+/// if `$TERM` ends with `256color`, and otherwise [`TerminalFormatter`].
+///
+/// If colors are not supported, e.g. when stdout is being piped,
+/// then `code` is printed as is, without any color sequences. If stdout
+/// is closed before all of the output is written, e.g. when piping to
+/// `head`, then the remaining output is discarded and `Ok(())` is returned.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let code = r#"fn main() {
+///     println!("Hello, world!");
+/// }"#;
+///
+/// pygmentize::print_highlighted(code, Some("rust"))?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn print_highlighted(code: impl AsRef<str>, lang: Option<&str>) -> Result<(), PygmentizeError> {
+    let code = code.as_ref();
+
+    let output = match ColorSupport::detect() {
+        ColorSupport::None => {
+            let mut code = code.to_owned();
+            if !code.ends_with('\n') {
+                code.push('\n');
+            }
+            code
+        }
+        ColorSupport::Basic => highlight(code, lang, &TerminalFormatter::default())?,
+        ColorSupport::Ansi256 => highlight(code, lang, &Terminal256Formatter::default())?,
+        ColorSupport::TrueColor => highlight(code, lang, &TerminalTrueColorFormatter::default())?,
+    };
+
+    let mut stdout = io::stdout().lock();
+    match stdout
+        .write_all(output.as_bytes())
+        .and_then(|_| stdout.flush())
+    {
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        res => res.map_err(PygmentizeError::Io),
+    }
+}
+
+/// Applies syntax highlighting to `code` written in `lang`, using
+/// a terminal formatter, and returns the highlighted lines, i.e. the
+/// line number of `lines[i]` is `i + 1`. The line endings are removed.
+///
+/// The terminal formatters reset the colors at the end of each line,
+/// so each line can be rendered independently, e.g. as a separate
+/// line widget in a TUI, or with only a range of the lines visible.
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::Terminal256Formatter;
+///
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let code = "/* A comment\nspanning lines */\nint x;";
+/// let lines = pygmentize::highlight_terminal_lines(code, Some("c"), &Terminal256Formatter::default())?;
+/// assert_eq!(lines.len(), 3);
+/// for (i, line) in lines.iter().enumerate() {
+///     println!("{:>3} {line}", i + 1);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn highlight_terminal_lines<F>(
+    code: impl AsRef<str>,
+    lang: Option<&str>,
+    fmt: &F,
+) -> Result<Vec<String>, PygmentizeError>
+where
+    F: PygmentizeFormatter,
+{
+    let output = highlight(code, lang, fmt)?;
+    Ok(output.lines().map(str::to_owned).collect())
+}
+
+/// Applies syntax highlighting to `code` written in `lang`, using
+/// a terminal formatter, and frames the output in a box drawn with
+/// Unicode box-drawing characters, optionally with a `title`.
+///
+/// The color sequences are not counted when computing the width of
+/// the lines, so the box is aligned regardless of the colors. Wide
+/// characters, e.g. CJK and emoji, are counted as 2 columns. Tabs
+/// are expanded to 4 spaces.
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::TerminalFormatter;
+///
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let code = "fn main() {}";
+/// let boxed = pygmentize::highlight_boxed(code, Some("rust"), Some("main.rs"), &TerminalFormatter::default())?;
+/// println!("{boxed}");
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Which outputs _(without colors)_:
+///
+/// ```text
+/// ╭─ main.rs ────╮
+/// │ fn main() {} │
+/// ╰──────────────╯
+/// ```
+///
+/// Wide characters are aligned by their display width:
+///
+/// ```rust
+/// use pygmentize::TerminalFormatter;
+///
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let code = r#"let s = "你好";"#;
+/// let boxed = pygmentize::highlight_boxed(code, Some("rust"), None, &TerminalFormatter::default())?;
+/// assert_eq!(
+///     pygmentize::strip_ansi(&boxed),
+///     "╭─────────────────╮\n\
+///      │ let s = \"你好\"; │\n\
+///      ╰─────────────────╯\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn highlight_boxed<F>(
+    code: impl AsRef<str>,
+    lang: Option<&str>,
+    title: Option<&str>,
+    fmt: &F,
+) -> Result<String, PygmentizeError>
+where
+    F: PygmentizeFormatter,
+{
+    let output = highlight(code, lang, fmt)?;
+    let output = output.replace('\t', "    ");
+    let lines = output.lines().collect::<Vec<_>>();
+
+    let title_width = title.map_or(0, |title| ansi_width(title) + 2);
+    let width = lines
+        .iter()
+        .map(|line| ansi_width(line))
+        .max()
+        .unwrap_or(0)
+        .max(title_width);
+
+    let mut boxed = String::with_capacity(output.len() + ((lines.len() + 2) * (width + 8)));
+
+    boxed.push_str("╭─");
+    if let Some(title) = title {
+        boxed.push(' ');
+        boxed.push_str(title);
+        boxed.push(' ');
+    }
+    push_repeated(&mut boxed, '─', (width - title_width) + 1);
+    boxed.push_str("╮\n");
+
+    for line in lines {
+        boxed.push_str("│ ");
+        boxed.push_str(line);
+        push_repeated(&mut boxed, ' ', width - ansi_width(line));
+        boxed.push_str(" │\n");
+    }
+
+    boxed.push('╰');
+    push_repeated(&mut boxed, '─', width + 2);
+    boxed.push_str("╯\n");
+
+    Ok(boxed)
+}
+
+fn push_repeated(s: &mut String, c: char, n: usize) {
+    s.extend(std::iter::repeat_n(c, n));
+}
+
+/// Removes ANSI escape sequences from `s`, e.g. the `"\x1b[31m"` color
+/// sequences output by [`TerminalFormatter`], such that terminal output
+/// can be stored as plain text, e.g. in a log file.
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::TerminalFormatter;
+///
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let output = pygmentize::highlight("fn main() {}", Some("rust"), &TerminalFormatter::default())?;
+/// assert_ne!(output, "fn main() {}\n");
+/// assert_eq!(pygmentize::strip_ansi(&output), "fn main() {}\n");
+/// # Ok(())
+/// # }
+/// ```
+pub fn strip_ansi(s: &str) -> String {
+    visible_chars(s).collect()
+}
+
+/// Returns the number of columns `s` occupies in a terminal, excluding
+/// ANSI escape sequences, e.g. `"\x1b[31m"`.
+fn ansi_width(s: &str) -> usize {
+    visible_chars(s)
+        .map(|c| UnicodeWidthChar::width(c).unwrap_or(0))
+        .sum()
+}
+
+/// Returns the characters of `s`, excluding ANSI escape sequences.
+fn visible_chars(s: &str) -> impl Iterator<Item = char> + '_ {
+    let mut chars = s.chars();
+    std::iter::from_fn(move || loop {
+        let c = chars.next()?;
+        if c != '\x1b' {
+            return Some(c);
+        }
+
+        // CSI sequences are terminated by a byte in the range `@` to `~`
+        if chars.next() == Some('[') {
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        }
+    })
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum ColorSupport {
+    None,
+    Basic,
+    Ansi256,
+    TrueColor,
+}
+
+impl ColorSupport {
+    fn detect() -> Self {
+        if !supports_color() {
+            return Self::None;
+        }
+
+        let colorterm = env::var("COLORTERM").unwrap_or_default();
+        let term = env::var("TERM").unwrap_or_default();
+        if (colorterm == "truecolor") || (colorterm == "24bit") {
+            Self::TrueColor
+        } else if term.ends_with("256color") {
+            Self::Ansi256
+        } else {
+            Self::Basic
+        }
+    }
+}
+
+/// Returns `true` if color sequences written to stdout will be shown,
+/// i.e. whether to use a terminal formatter.
+///
+/// The following conventions are followed, in order:
+///
+/// - If `$NO_COLOR` is set and not empty, then `false`.
+///   See <https://no-color.org>.
+/// - If `$CLICOLOR_FORCE` is set and not `0`, then `true`.
+/// - If stdout is not a terminal, e.g. when piped, then `false`.
+/// - If `$TERM` is `dumb` or `$CLICOLOR` is `0`, then `false`.
+/// - Otherwise `true`.
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::TerminalFormatter;
+///
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let code = "fn main() {}";
+///
+/// if pygmentize::supports_color() {
+///     print!("{}", pygmentize::highlight(code, Some("rust"), &TerminalFormatter::default())?);
+/// } else {
+///     println!("{code}");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn supports_color() -> bool {
+    if env::var_os("NO_COLOR").is_some_and(|val| !val.is_empty()) {
+        return false;
+    }
+
+    if env::var_os("CLICOLOR_FORCE").is_some_and(|val| val != "0") {
+        return true;
+    }
+
+    if !io::stdout().is_terminal() {
+        return false;
+    }
+
+    if env::var_os("TERM").is_some_and(|term| term == "dumb") {
+        return false;
+    }
+
+    if env::var_os("CLICOLOR").is_some_and(|val| val == "0") {
+        return false;
+    }
+
+    true
+}
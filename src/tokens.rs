@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::{highlight_bytes_with, Control, PygmentizeError, RawTokenFormatter};
+
+/// The type of a token, e.g. `Token.Keyword` or `Token.Name.Function`.
+///
+/// See <https://pygments.org/docs/tokens/> for the token types.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Debug)]
+pub struct TokenType(String);
+
+impl TokenType {
+    /// Returns the token type, e.g. `"Token.Name.Function"`.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns `true` if `self` is `parent` or a subtype of it, e.g.
+    /// `Token.Name.Function` is a subtype of `Token.Name` and `Token`,
+    /// like Pygments' `token in Token.Name`.
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+    /// let tokens = pygmentize::tokenize("fn main() {}", Some("rust"))?;
+    /// let (token_type, value) = &tokens[2];
+    ///
+    /// assert_eq!(value, "main");
+    /// assert!(token_type.is_subtype_of("Token.Name"));
+    /// assert!(!token_type.is_subtype_of("Token.Keyword"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn is_subtype_of(&self, parent: &str) -> bool {
+        match self.0.strip_prefix(parent) {
+            Some(rest) => rest.is_empty() || rest.starts_with('.'),
+            None => false,
+        }
+    }
+}
+
+impl fmt::Display for TokenType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Splits `code` into tokens using the lexer for `lang`, i.e.
+/// pairs of the token type and the text of the token, e.g. for
+/// custom rendering on top of Pygments' lexers.
+///
+/// The text of all the tokens concatenated is the code, after the
+/// lexer options have been applied, e.g. a trailing newline is added,
+/// see [`LexerOptions`](crate::LexerOptions).
+///
+/// Returns [`PygmentizeError::InvalidOutput`] if the output of
+/// the [`RawTokenFormatter`] cannot be parsed.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let tokens = pygmentize::tokenize("let s = \"a\\tb\";", Some("rust"))?;
+///
+/// let (token_type, value) = &tokens[0];
+/// assert_eq!(token_type.as_str(), "Token.Keyword.Declaration");
+/// assert_eq!(value, "let");
+///
+/// let code = tokens.iter().map(|(_, value)| value.as_str()).collect::<String>();
+/// assert_eq!(code, "let s = \"a\\tb\";\n");
+/// # Ok(())
+/// # }
+/// ```
+pub fn tokenize(
+    code: impl AsRef<str>,
+    lang: Option<&str>,
+) -> Result<Vec<(TokenType, String)>, PygmentizeError> {
+    // Not `highlight()`, as post-processing and transforms, see
+    // `add_transform()`, would corrupt the raw tokens
+    let raw = highlight_bytes_with(
+        None,
+        code.as_ref().as_bytes(),
+        lang,
+        &RawTokenFormatter::new(),
+        None,
+        Control::default(),
+    )?;
+    let raw = String::from_utf8(raw).map_err(PygmentizeError::InvalidUtf8)?;
+
+    raw.lines()
+        .map(|line| {
+            line.split_once('\t')
+                .and_then(|(token_type, value)| {
+                    let value = parse_repr(value)?;
+                    Some((TokenType(token_type.to_owned()), value))
+                })
+                .ok_or_else(|| PygmentizeError::InvalidOutput(line.to_owned()))
+        })
+        .collect()
+}
+
+/// Counts the occurrences of each token type in `code`, e.g.
+/// `"Token.Keyword"` or `"Token.Name.Function"`, as produced by
+/// the lexer for `lang`.
+///
+/// The counts of multiple snippets can be summed, to find out which
+/// token types are relevant to style, when e.g. building a custom style.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let histogram = pygmentize::token_type_histogram("fn f() {}\nfn g() {}", Some("rust"))?;
+/// assert_eq!(histogram["Token.Keyword"], 2);
+/// assert_eq!(histogram["Token.Name.Function"], 2);
+/// # Ok(())
+/// # }
+/// ```
+pub fn token_type_histogram(
+    code: impl AsRef<str>,
+    lang: Option<&str>,
+) -> Result<HashMap<String, usize>, PygmentizeError> {
+    let mut histogram = HashMap::new();
+    for (token_type, _value) in tokenize(code, lang)? {
+        *histogram.entry(token_type.0).or_default() += 1;
+    }
+    Ok(histogram)
+}
+
+/// Parses the Python `repr()` of a `str`, e.g. `'it\'s'` or `"it's"`.
+/// Returns `None` if `repr` is not a quoted string.
+fn parse_repr(repr: &str) -> Option<String> {
+    let quote = repr.chars().next().filter(|&c| (c == '\'') || (c == '"'))?;
+    let inner = repr[1..].strip_suffix(quote)?;
+
+    let mut value = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+
+        let c = match chars.next()? {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            'a' => '\u{7}',
+            'b' => '\u{8}',
+            'f' => '\u{c}',
+            'v' => '\u{b}',
+            'x' => parse_hex(&mut chars, 2)?,
+            'u' => parse_hex(&mut chars, 4)?,
+            'U' => parse_hex(&mut chars, 8)?,
+            // i.e. `\\`, `\'`, and `\"`
+            c => c,
+        };
+        value.push(c);
+    }
+    Some(value)
+}
+
+fn parse_hex(chars: &mut impl Iterator<Item = char>, len: usize) -> Option<char> {
+    let hex = chars.take(len).collect::<String>();
+    if hex.len() != len {
+        return None;
+    }
+    char::from_u32(u32::from_str_radix(&hex, 16).ok()?)
+}
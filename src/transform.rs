@@ -0,0 +1,70 @@
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+static TRANSFORMS: RwLock<Vec<Transform>> = RwLock::new(Vec::new());
+
+/// A post-processing step, which is applied to the output of
+/// every [`highlight()`](crate::highlight) call.
+///
+/// See [`add_transform()`].
+#[derive(Clone)]
+pub struct Transform(Arc<dyn Fn(String) -> String + Send + Sync>);
+
+impl Transform {
+    pub fn new<F>(f: F) -> Self
+    where
+        F: Fn(String) -> String + Send + Sync + 'static,
+    {
+        Self(Arc::new(f))
+    }
+
+    pub fn apply(&self, output: String) -> String {
+        (self.0)(output)
+    }
+}
+
+impl fmt::Debug for Transform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Transform").finish_non_exhaustive()
+    }
+}
+
+/// Register a transform, which is applied to the output of every
+/// [`highlight()`](crate::highlight) call. Transforms are applied in
+/// the order they were added, after the formatter's own post-processing.
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::HtmlFormatter;
+///
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// pygmentize::add_transform(|html| html.replace("<pre>", r#"<pre data-lang="rust">"#));
+/// pygmentize::add_transform(|html| html.trim_end().to_owned());
+///
+/// let html = pygmentize::highlight("fn main() {}", Some("rust"), &HtmlFormatter::default())?;
+/// assert!(html.contains(r#"<pre data-lang="rust">"#));
+/// assert!(html.ends_with("</div>"));
+/// # pygmentize::clear_transforms();
+/// # Ok(())
+/// # }
+/// ```
+pub fn add_transform<F>(f: F)
+where
+    F: Fn(String) -> String + Send + Sync + 'static,
+{
+    TRANSFORMS.write().unwrap().push(Transform::new(f));
+}
+
+/// Remove all transforms registered with [`add_transform()`].
+pub fn clear_transforms() {
+    TRANSFORMS.write().unwrap().clear();
+}
+
+pub(crate) fn apply_transforms(output: String) -> String {
+    // Clone to avoid holding the lock while applying the transforms
+    let transforms = TRANSFORMS.read().unwrap().clone();
+    transforms
+        .iter()
+        .fold(output, |output, transform| transform.apply(output))
+}
@@ -0,0 +1,64 @@
+use std::borrow::Cow;
+use std::sync::RwLock;
+
+static MAX_LINE_LENGTH: RwLock<Option<usize>> = RwLock::new(None);
+
+/// Appended to lines, which were truncated.
+const ELLIPSIS: char = '…';
+
+/// Truncate lines longer than `max_line_length` characters, before the
+/// code is highlighted, e.g. for minified files where a single line can
+/// be hundreds of KB. Truncated lines are marked by appending `…`.
+///
+/// Lines are truncated at character boundaries, the line endings
+/// are preserved. `None` (the default) disables truncation.
+///
+/// _This affects the code passed to `pygmentize`, i.e. if the code is
+/// truncated in the middle of e.g. a string, then the remainder of the
+/// line is highlighted as part of that string._
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::TerminalFormatter;
+///
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// pygmentize::set_max_line_length(Some(10));
+///
+/// let code = "let x = [1, 2, 3, 4, 5, 6, 7, 8, 9];\nlet y = 1;\n";
+/// let output = pygmentize::highlight(code, Some("text"), &TerminalFormatter::default())?;
+/// assert_eq!(output, "let x = [1…\nlet y = 1;\n");
+/// # pygmentize::set_max_line_length(None);
+/// # Ok(())
+/// # }
+/// ```
+pub fn set_max_line_length(max_line_length: Option<usize>) {
+    *MAX_LINE_LENGTH.write().unwrap() = max_line_length;
+}
+
+/// Truncates the lines of `code` longer than the
+/// length set with [`set_max_line_length()`].
+pub(crate) fn truncate_lines(code: &str) -> Cow<'_, str> {
+    let Some(max) = *MAX_LINE_LENGTH.read().unwrap() else {
+        return Cow::Borrowed(code);
+    };
+
+    // Avoid allocating if no lines need to be truncated, which is the common case
+    if !code.lines().any(|line| line.chars().nth(max).is_some()) {
+        return Cow::Borrowed(code);
+    }
+
+    let mut out = String::with_capacity(code.len());
+    for line in code.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\r', '\n']);
+        match content.char_indices().nth(max) {
+            Some((end, _)) => {
+                out.push_str(&content[..end]);
+                out.push(ELLIPSIS);
+                out.push_str(&line[content.len()..]);
+            }
+            None => out.push_str(line),
+        }
+    }
+    Cow::Owned(out)
+}
@@ -0,0 +1,188 @@
+use std::borrow::Cow;
+use std::fmt::Write;
+
+use crate::formatters::Options;
+use crate::html::unescape;
+use crate::{PygmentizeError, PygmentizeFormatter};
+
+const FONT_FAMILY: &str = r#"("DejaVu Sans Mono", "Courier New")"#;
+
+/// Format tokens as Typst code, i.e. a `#block` with a `text()` call
+/// per token, colored using the style, for embedding in Typst documents.
+///
+/// Pygments has no Typst formatter, instead the colors are resolved by
+/// Pygments' `html` formatter with inline styles, which is then
+/// converted to Typst.
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::TypstFormatter;
+///
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let fmt = TypstFormatter {
+///     style: Some(String::from("monokai")),
+/// };
+///
+/// let typst = pygmentize::highlight("fn main() {}", Some("rust"), &fmt)?;
+/// assert!(typst.starts_with(r##"#block(fill: rgb("#272822")"##));
+/// assert!(typst.contains(r##"text(fill: rgb("#66D9EF"), "fn")"##));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Hash, Debug, Default)]
+pub struct TypstFormatter {
+    /// The style, i.e. color scheme, e.g. `"monokai"`. If `None`,
+    /// Pygments uses the `"default"` style.
+    ///
+    /// See <https://pygments.org/styles/> for available styles.
+    pub style: Option<String>,
+}
+
+impl TypstFormatter {
+    pub const fn new() -> Self {
+        Self { style: None }
+    }
+}
+
+impl PygmentizeFormatter for TypstFormatter {
+    const SHORT_NAME: &'static str = "html";
+
+    fn options_str(&self) -> Option<Cow<'_, str>> {
+        let mut opts = Options::new();
+        opts.push("noclasses", "true");
+        if let Some(style) = &self.style {
+            opts.push("style", style);
+        }
+        opts.finish()
+    }
+
+    fn style(&self) -> Option<&str> {
+        self.style.as_deref()
+    }
+
+    fn post_process(&self, _code: &str, output: String) -> Result<String, PygmentizeError> {
+        Ok(to_typst(&output))
+    }
+}
+
+/// The style of a `<span>`, i.e. its `style` attribute.
+#[derive(Clone, Copy, Default, Debug)]
+struct SpanStyle<'a> {
+    color: Option<&'a str>,
+    bold: bool,
+    italic: bool,
+}
+
+impl<'a> SpanStyle<'a> {
+    /// Parses e.g. `color: #008000; font-weight: bold`,
+    /// where missing properties are inherited from `parent`.
+    fn parse(style: &'a str, parent: Self) -> Self {
+        let mut span = parent;
+        for decl in style.split(';') {
+            let Some((prop, value)) = decl.split_once(':') else {
+                continue;
+            };
+            match (prop.trim(), value.trim()) {
+                ("color", color) => span.color = Some(color),
+                ("font-weight", weight) => span.bold = weight == "bold",
+                ("font-style", style) => span.italic = style == "italic",
+                _ => {}
+            }
+        }
+        span
+    }
+}
+
+/// Converts the output of the `html` formatter with `noclasses=true`.
+fn to_typst(html: &str) -> String {
+    // e.g. `<div class="highlight" style="background: #f8f8f8">`
+    let background = html
+        .split_once(r#"style="background: "#)
+        .and_then(|(_, rest)| rest.split_once('"'))
+        .map(|(background, _)| background.trim_end_matches(';'));
+
+    let code = html
+        .split_once("<pre")
+        .and_then(|(_, rest)| rest.split_once('>'))
+        .map_or(html, |(_, code)| code);
+    let code = code.rsplit_once("</pre>").map_or(code, |(code, _)| code);
+    let code = code.strip_suffix('\n').unwrap_or(code);
+
+    let mut out = String::with_capacity(code.len() * 2);
+    out.push_str("#block(");
+    if let Some(background) = background {
+        write!(out, r#"fill: rgb("{background}"), "#).unwrap();
+    }
+    out.push_str("inset: 8pt, radius: 4pt, width: 100%, {\n");
+    writeln!(out, "  set text(font: {FONT_FAMILY})").unwrap();
+
+    // Spans are only nested for `hl_lines`, but handle any nesting
+    let mut stack = vec![SpanStyle::default()];
+    let mut rest = code;
+    while !rest.is_empty() {
+        if let Some(tag) = rest.strip_prefix("</span>") {
+            if stack.len() > 1 {
+                stack.pop();
+            }
+            rest = tag;
+        } else if rest.starts_with("<span") {
+            let end = rest.find('>').map_or(rest.len(), |end| end + 1);
+            let tag = &rest[..end];
+            let parent = *stack.last().unwrap();
+            let span = match tag.split_once(r#"style=""#) {
+                Some((_, style)) => SpanStyle::parse(style.split('"').next().unwrap(), parent),
+                None => parent,
+            };
+            stack.push(span);
+            rest = &rest[end..];
+        } else if rest.starts_with('<') {
+            // Other tags are not output with `noclasses=true`, so skip them
+            let end = rest.find('>').map_or(rest.len(), |end| end + 1);
+            rest = &rest[end..];
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            push_text(&mut out, &unescape(&rest[..end]), *stack.last().unwrap());
+            rest = &rest[end..];
+        }
+    }
+
+    out.push_str("})\n");
+    out
+}
+
+/// Pushes `text(...)` calls for `text`, and `linebreak()` for newlines.
+fn push_text(out: &mut String, text: &str, style: SpanStyle<'_>) {
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            out.push_str("  linebreak()\n");
+        }
+        if line.is_empty() {
+            continue;
+        }
+
+        out.push_str("  text(");
+        if let Some(color) = style.color {
+            write!(out, r#"fill: rgb("{color}"), "#).unwrap();
+        }
+        if style.bold {
+            out.push_str(r#"weight: "bold", "#);
+        }
+        if style.italic {
+            out.push_str(r#"style: "italic", "#);
+        }
+        out.push('"');
+        for c in line.chars() {
+            match c {
+                '"' => out.push_str(r#"\""#),
+                '\\' => out.push_str(r"\\"),
+                // Non-breaking, such that indentation is not collapsed
+                ' ' => out.push_str(r"\u{a0}"),
+                '\t' => out.push_str(r"\u{a0}\u{a0}\u{a0}\u{a0}"),
+                '\r' => {}
+                c => out.push(c),
+            }
+        }
+        out.push_str("\")\n");
+    }
+}
@@ -0,0 +1,97 @@
+use std::io;
+use std::os::unix::process::CommandExt;
+use std::process::Command;
+use std::sync::RwLock;
+
+static MEMORY_LIMIT: RwLock<Option<u64>> = RwLock::new(None);
+
+static NICE: RwLock<Option<i32>> = RwLock::new(None);
+
+/// Limit the address space (`RLIMIT_AS`) of the `pygmentize` child
+/// process to `limit` bytes, such that a pathological input causing
+/// Pygments to use excessive memory, gets the child killed instead of
+/// affecting the current process. Use `None` to remove the limit,
+/// which is the default.
+///
+/// If the limit is exceeded, then `pygmentize` exits with an error,
+/// which is returned as [`PygmentizeError::Pygmentize`](crate::PygmentizeError::Pygmentize).
+///
+/// _Note that the Python interpreter itself reserves a considerable
+/// amount of address space, so the limit should be generous,
+/// e.g. at least a few hundred megabytes._
+///
+/// # Example
+///
+/// ```no_run
+/// // Limit pygmentize to 512 MiB
+/// pygmentize::set_memory_limit(Some(512 * 1024 * 1024));
+/// ```
+pub fn set_memory_limit(limit: Option<u64>) {
+    *MEMORY_LIMIT.write().unwrap() = limit;
+}
+
+/// Set the niceness of the `pygmentize` child process to `level`, which
+/// ranges from `-20` (highest priority) to `19` (lowest priority), such
+/// that e.g. batch highlighting yields to interactive processes. Use
+/// `None` to inherit the niceness of the current process, which is
+/// the default.
+///
+/// If the niceness cannot be set, e.g. lowering it without the required
+/// privileges, then spawning fails with
+/// [`PygmentizeError::Process`](crate::PygmentizeError::Process).
+///
+/// # Example
+///
+/// ```no_run
+/// // Run pygmentize at the lowest priority
+/// pygmentize::set_nice(Some(19));
+/// ```
+pub fn set_nice(level: Option<i32>) {
+    *NICE.write().unwrap() = level;
+}
+
+/// Applies the process settings, e.g. [`set_memory_limit()`] and [`set_nice()`],
+/// to `cmd` before it is spawned.
+pub(crate) fn configure_command(cmd: &mut Command) {
+    if let Some(limit) = *MEMORY_LIMIT.read().unwrap() {
+        set_rlimit_as(cmd, limit);
+    }
+
+    if let Some(level) = *NICE.read().unwrap() {
+        set_priority(cmd, level);
+    }
+}
+
+#[allow(unsafe_code)]
+fn set_rlimit_as(cmd: &mut Command, limit: u64) {
+    let limit = libc::rlimit {
+        rlim_cur: limit as libc::rlim_t,
+        rlim_max: limit as libc::rlim_t,
+    };
+
+    // Safety: `setrlimit()` is async-signal-safe and the closure
+    // does not allocate, so it is safe to call between fork and exec
+    unsafe {
+        cmd.pre_exec(move || {
+            if libc::setrlimit(libc::RLIMIT_AS, &limit) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+}
+
+#[allow(unsafe_code)]
+fn set_priority(cmd: &mut Command, level: i32) {
+    // Safety: `setpriority()` is async-signal-safe and the closure
+    // does not allocate, so it is safe to call between fork and exec
+    unsafe {
+        cmd.pre_exec(move || {
+            // `who` being `0` refers to the calling process, i.e. the child
+            if libc::setpriority(libc::PRIO_PROCESS, 0, level) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+}
@@ -0,0 +1,153 @@
+use std::ffi::{OsStr, OsString};
+use std::fmt;
+use std::sync::Mutex;
+
+use crate::{bin_key, run_cmd_with_bin, PygmentizeError};
+
+/// The version of the installed Pygments, as reported by `pygmentize -V`.
+///
+/// Versions are ordered by `major`, then `minor`, then `patch`:
+///
+/// ```rust
+/// use pygmentize::Version;
+///
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// if pygmentize::version()? < Version::new(2, 14, 0) {
+///     eprintln!("warning: Pygments 2.14 or newer is recommended");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl Version {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Returns the version of the installed Pygments, e.g. to only use
+/// options supported by it. Pre-release suffixes are ignored, e.g.
+/// `2.16.0rc1` is returned as `2.16.0`.
+///
+/// Returns [`PygmentizeError::InvalidVersion`] if the
+/// output of `pygmentize -V` cannot be parsed.
+///
+/// The version is looked up once per path to the `pygmentize` binary,
+/// subsequent calls return the cached version.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let version = pygmentize::pygmentize_version()?;
+/// println!("Pygments {version}");
+/// # Ok(())
+/// # }
+/// ```
+pub fn pygmentize_version() -> Result<Version, PygmentizeError> {
+    version_with(None)
+}
+
+/// Same as [`pygmentize_version()`].
+pub fn version() -> Result<Version, PygmentizeError> {
+    pygmentize_version()
+}
+
+/// Same as [`pygmentize_version()`], but for `bin` instead of the global binary, if given.
+pub(crate) fn version_with(bin: Option<&OsStr>) -> Result<Version, PygmentizeError> {
+    static CACHE: Mutex<Option<(OsString, Version)>> = Mutex::new(None);
+
+    let key = bin_key(bin);
+
+    if let Some((cached_bin, version)) = CACHE.lock().unwrap().as_ref() {
+        if *cached_bin == key {
+            return Ok(*version);
+        }
+    }
+
+    let output = run_cmd_with_bin(bin, ["-V"], None)?;
+    let output = String::from_utf8(output).map_err(PygmentizeError::InvalidUtf8)?;
+    let version = parse_version(&output).ok_or(PygmentizeError::InvalidVersion(output))?;
+
+    *CACHE.lock().unwrap() = Some((key, version));
+
+    Ok(version)
+}
+
+/// Returns [`PygmentizeError::UnsupportedVersion`] if the installed
+/// Pygments is older than `required`, see [`pygmentize_version()`].
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::Version;
+///
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// pygmentize::require_min_version(Version::new(2, 0, 0))?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn require_min_version(required: Version) -> Result<(), PygmentizeError> {
+    require_min_version_with(None, required)
+}
+
+/// Same as [`require_min_version()`], but for `bin` instead of the global binary, if given.
+pub(crate) fn require_min_version_with(
+    bin: Option<&OsStr>,
+    required: Version,
+) -> Result<(), PygmentizeError> {
+    let found = version_with(bin)?;
+    if found < required {
+        return Err(PygmentizeError::UnsupportedVersion(found, required));
+    }
+    Ok(())
+}
+
+/// Parses the output of `pygmentize -V`, e.g.:
+///
+/// ```text
+/// Pygments version 2.15.1, (c) 2006-2023 by Georg Brandl, Matthäus Chajdas and contributors.
+/// ```
+///
+/// Pre-release and local suffixes are ignored, e.g. `2.16.0rc1`,
+/// `2.16.0.dev0`, and `2.16.0+local` are all parsed as `2.16.0`.
+fn parse_version(output: &str) -> Option<Version> {
+    let (_, version) = output.split_once("version ")?;
+    let version = version.split([',', ' ', '+']).next()?.trim();
+
+    let mut parts = version.split('.');
+    let major = parse_leading_number(parts.next()?)?;
+    let minor = parse_leading_number(parts.next()?)?;
+    let patch = match parts.next() {
+        Some(patch) => parse_leading_number(patch).unwrap_or(0),
+        None => 0,
+    };
+
+    Some(Version {
+        major,
+        minor,
+        patch,
+    })
+}
+
+/// Parses the leading digits of `s`, e.g. `0` of `0rc1`.
+fn parse_leading_number(s: &str) -> Option<u32> {
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    s[..end].parse().ok()
+}
@@ -0,0 +1,108 @@
+use std::io::{self, Read, Write};
+use std::thread;
+
+use crate::truncate::truncate_lines;
+use crate::{
+    check_empty_output, exit_error, fmt_args, spawn_cmd, PygmentizeError, PygmentizeFormatter,
+};
+
+/// Same as [`highlight()`](crate::highlight), but the output of
+/// `pygmentize` is written directly to `out`, as it is produced,
+/// instead of being collected into a `String`. This avoids holding
+/// the whole output in memory, e.g. when highlighting large files.
+///
+/// As the output is never buffered, it is neither validated as UTF-8
+/// nor post-processed, i.e. [`PygmentizeFormatter::post_process()`] and
+/// [transforms](crate::add_transform) are not applied. For formatters
+/// with options implemented by post-processing, e.g.
+/// [`HtmlFormatter::copyable`](crate::HtmlFormatter::copyable),
+/// use [`highlight()`](crate::highlight) instead.
+///
+/// If `pygmentize` fails, then `out` might already contain
+/// part of the output. Returns [`PygmentizeError::Io`] if
+/// writing to `out` fails.
+///
+/// # Example
+///
+/// ```rust
+/// use pygmentize::TerminalFormatter;
+///
+/// # fn main() -> Result<(), pygmentize::PygmentizeError> {
+/// let mut out = Vec::new();
+/// pygmentize::highlight_to_writer("fn main() {}", Some("rust"), &TerminalFormatter::default(), &mut out)?;
+/// assert!(!out.is_empty());
+///
+/// let stdout = std::io::stdout();
+/// pygmentize::highlight_to_writer("fn main() {}", Some("rust"), &TerminalFormatter::default(), &mut stdout.lock())?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn highlight_to_writer<F, W>(
+    code: impl AsRef<str>,
+    lang: Option<&str>,
+    fmt: &F,
+    out: &mut W,
+) -> Result<(), PygmentizeError>
+where
+    F: PygmentizeFormatter,
+    W: Write + ?Sized,
+{
+    let code = truncate_lines(code.as_ref());
+    let code = &*code;
+    fmt.validate_input(code)?;
+
+    let args = fmt_args(lang, fmt)?;
+    let mut child = spawn_cmd(args.iter().map(|arg| &**arg), true)?;
+
+    let mut stdin = child.stdin.take().expect("expected stdin");
+    let mut stdout = child.stdout.take().expect("expected stdout");
+    let mut stderr = child.stderr.take().expect("expected stderr");
+
+    // stdin is written and stderr is read on separate threads, such
+    // that `pygmentize` never blocks on a full pipe, while stdout is
+    // copied into `out` on this thread, as `out` might not be `Send`
+    let (written, stdin_res, stderr) = thread::scope(|s| {
+        let stdin_thread = s.spawn(move || stdin.write_all(code.as_bytes()));
+        let stderr_thread = s.spawn(move || {
+            let mut buf = Vec::new();
+            stderr.read_to_end(&mut buf).map(|_| buf)
+        });
+
+        let written = io::copy(&mut stdout, out).and_then(|n| out.flush().map(|_| n));
+        if written.is_err() {
+            // Errors are ignored, as the child might have exited in the meantime
+            let _ = child.kill();
+        }
+        drop(stdout);
+
+        let stdin_res = stdin_thread.join().expect("stdin thread panicked");
+        let stderr = stderr_thread.join().expect("stderr thread panicked");
+        (written, stdin_res, stderr)
+    });
+
+    let status = child.wait().map_err(PygmentizeError::Process)?;
+
+    // Executing `pygmentize` causes `ENABLE_VIRTUAL_TERMINAL_PROCESSING` to get turned off
+    #[cfg(windows)]
+    crate::enable_virtual_terminal_processing();
+
+    let written = written.map_err(PygmentizeError::Io)?;
+
+    if !status.success() {
+        let stderr = stderr.map_err(PygmentizeError::Process)?;
+        let stderr = String::from_utf8_lossy(&stderr).into_owned();
+        return Err(exit_error(status, stderr));
+    }
+
+    match stdin_res {
+        // `pygmentize` exiting before reading all of stdin is
+        // reported through its exit status instead
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => {}
+        res => res.map_err(PygmentizeError::Process)?,
+    }
+
+    if written == 0 {
+        check_empty_output(code.as_bytes(), b"")?;
+    }
+    Ok(())
+}
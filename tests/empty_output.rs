@@ -0,0 +1,22 @@
+//! Separate from `tests/mock.rs`, as the setting is global.
+
+#![cfg(unix)]
+
+use pygmentize::{HtmlFormatter, PygmentizeError};
+
+#[test]
+fn empty_output() {
+    pygmentize::set_bin_path(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/mock/pygmentize"
+    ));
+    pygmentize::set_reject_empty_output(true);
+
+    let res = pygmentize::highlight("empty", Some("rust"), &HtmlFormatter::default());
+    assert!(matches!(res, Err(PygmentizeError::EmptyOutput)));
+
+    pygmentize::set_reject_empty_output(false);
+
+    let res = pygmentize::highlight("empty", Some("rust"), &HtmlFormatter::default());
+    assert_eq!(res.unwrap(), "");
+}
@@ -0,0 +1,20 @@
+//! Separate from `tests/mock.rs`, as the binary paths are global.
+
+#![cfg(unix)]
+
+use pygmentize::{HtmlFormatter, PygmentizeError};
+
+#[test]
+fn fallback() {
+    pygmentize::set_bin_path(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/mock/missing"));
+
+    let res = pygmentize::highlight("", Some("rust"), &HtmlFormatter::default());
+    assert!(matches!(res, Err(PygmentizeError::NotFound(_))));
+
+    pygmentize::set_fallback_bin_path(Some(
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/mock/pygmentize").into(),
+    ));
+
+    let args = pygmentize::highlight("", Some("rust"), &HtmlFormatter::default()).unwrap();
+    assert_eq!(args, "-f\nhtml\n-l\nrust\n");
+}
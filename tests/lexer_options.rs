@@ -0,0 +1,50 @@
+//! Separate from `tests/mock.rs`, as the setting is global.
+
+#![cfg(unix)]
+
+use pygmentize::{LexerOptions, TerminalFormatter};
+
+#[test]
+fn lexer_options() {
+    pygmentize::set_bin_path(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/mock/pygmentize"
+    ));
+
+    let mut defaults = LexerOptions {
+        tab_size: Some(4),
+        strip_newlines: Some(false),
+        ..LexerOptions::default()
+    };
+    defaults
+        .options
+        .insert("startinline".into(), "false".into());
+    pygmentize::set_lexer_options(defaults);
+
+    let fmt = TerminalFormatter {
+        line_numbers: true,
+        ..TerminalFormatter::default()
+    };
+
+    let mut overrides = LexerOptions {
+        tab_size: Some(8),
+        ..LexerOptions::default()
+    };
+    overrides
+        .options
+        .insert("startinline".into(), "true".into());
+
+    let output =
+        pygmentize::highlight_with_lexer_options("", Some("php"), &fmt, Some(&overrides)).unwrap();
+    assert_eq!(
+        output.lines().nth(5),
+        Some("startinline=true,stripnl=false,tabsize=8,linenos=true")
+    );
+
+    // The defaults are unchanged
+    let output = pygmentize::highlight("", Some("php"), &fmt).unwrap();
+    assert_eq!(
+        output.lines().nth(5),
+        Some("startinline=false,stripnl=false,tabsize=4,linenos=true")
+    );
+}
@@ -0,0 +1,502 @@
+//! Tests the argument construction and error handling
+//! against a mock of `pygmentize`, see `tests/mock/pygmentize`.
+
+#![cfg(unix)]
+
+use std::sync::Once;
+use std::time::{Duration, Instant};
+
+use pygmentize::{
+    hl_lines, Filter, HtmlFormatter, ImageFormat, ImageFormatter, LatexFormatter, LexerOptions,
+    Pygmentize, PygmentizeError, PygmentizeFormatter, Terminal256Formatter, TerminalFormatter,
+};
+
+const MOCK: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/mock/pygmentize");
+
+fn setup() {
+    static SETUP: Once = Once::new();
+    SETUP.call_once(|| pygmentize::set_bin_path(MOCK));
+}
+
+/// Returns the arguments `pygmentize` was executed with.
+fn args<F>(input: &str, lang: Option<&str>, fmt: &F) -> Result<Vec<String>, PygmentizeError>
+where
+    F: PygmentizeFormatter,
+{
+    setup();
+    let output = pygmentize::highlight(input, lang, fmt)?;
+    Ok(output.lines().map(str::to_owned).collect())
+}
+
+#[test]
+fn lang() {
+    let args = args("", Some("rust"), &HtmlFormatter::default()).unwrap();
+    assert_eq!(args, ["-f", "html", "-l", "rust"]);
+}
+
+#[test]
+fn guess_lang() {
+    let args = args("", None, &HtmlFormatter::default()).unwrap();
+    assert_eq!(args, ["-f", "html", "-g"]);
+}
+
+#[test]
+fn alias() {
+    pygmentize::register_alias("mock-alias", "python");
+
+    let args = args("", Some("mock-alias"), &HtmlFormatter::default()).unwrap();
+    assert_eq!(args, ["-f", "html", "-l", "python"]);
+}
+
+#[test]
+fn options_and_filters() {
+    let fmt = HtmlFormatter {
+        copyable: true,
+        merge_tokens: true,
+        highlight_lines: hl_lines![1, 3..=4],
+        ..HtmlFormatter::default()
+    };
+
+    let args = args("", Some("rust"), &fmt).unwrap();
+    assert_eq!(
+        args,
+        [
+            "-f",
+            "html",
+            "-l",
+            "rust",
+            "-O",
+            "hl_lines=1 3 4,linenos=table",
+            "-F",
+            "tokenmerge",
+        ]
+    );
+}
+
+#[test]
+fn line_number_start() {
+    let mut fmt = HtmlFormatter {
+        line_number_start: Some(10),
+        ..HtmlFormatter::default()
+    };
+
+    // Only passed if line numbers are enabled
+    assert_eq!(
+        args("", Some("rust"), &fmt).unwrap(),
+        ["-f", "html", "-l", "rust"]
+    );
+
+    fmt.line_numbers = true;
+    assert_eq!(
+        args("", Some("rust"), &fmt).unwrap(),
+        [
+            "-f",
+            "html",
+            "-l",
+            "rust",
+            "-O",
+            "linenos=true,linenostart=10"
+        ]
+    );
+}
+
+#[test]
+fn custom_code_tags() {
+    let fmt = TerminalFormatter {
+        code_tags: true,
+        custom_code_tags: vec![String::from("TODO"), String::from("HACK")],
+        ..TerminalFormatter::default()
+    };
+
+    let args = args("", Some("rust"), &fmt).unwrap();
+    assert_eq!(
+        args,
+        [
+            "-f",
+            "terminal",
+            "-l",
+            "rust",
+            "-F",
+            "codetagify:codetags=TODO HACK",
+        ]
+    );
+}
+
+#[test]
+fn lexer_filters() {
+    setup();
+
+    let fmt = HtmlFormatter {
+        merge_tokens: true,
+        ..HtmlFormatter::default()
+    };
+    let lexer_options = LexerOptions {
+        filters: vec![
+            Filter::new("keywordcase").option("case", "upper"),
+            Filter::new("whitespace")
+                .option("spaces", "True")
+                .option("tabs", "True"),
+            Filter::new("highlight").option("names", "std::mem=swap"),
+        ],
+        ..LexerOptions::default()
+    };
+
+    let output =
+        pygmentize::highlight_with_lexer_options("", Some("rust"), &fmt, Some(&lexer_options))
+            .unwrap();
+    let args = output.lines().collect::<Vec<_>>();
+    assert_eq!(
+        args,
+        [
+            "-f",
+            "html",
+            "-l",
+            "rust",
+            "-F",
+            "keywordcase:case=upper",
+            "-F",
+            "whitespace:spaces=True,tabs=True",
+            "-F",
+            "highlight:names=std::mem=swap",
+            "-F",
+            "tokenmerge",
+        ]
+    );
+
+    for filter in [
+        Filter::new("highlight").option("names", "a,b"),
+        Filter::new("highlight").option("na=mes", "a"),
+        Filter::new("high:light"),
+    ] {
+        let lexer_options = LexerOptions {
+            filters: vec![filter],
+            ..LexerOptions::default()
+        };
+        let res =
+            pygmentize::highlight_with_lexer_options("", Some("rust"), &fmt, Some(&lexer_options));
+        assert!(matches!(
+            res,
+            Err(PygmentizeError::InvalidOption("lexer_options", _))
+        ));
+    }
+}
+
+#[test]
+fn line_numbers() {
+    let fmt = LatexFormatter {
+        line_numbers: true,
+        line_number_start: Some(10),
+        line_number_step: Some(5),
+        ..LatexFormatter::default()
+    };
+
+    let args = args("", Some("rust"), &fmt).unwrap();
+    assert_eq!(
+        args,
+        [
+            "-f",
+            "latex",
+            "-l",
+            "rust",
+            "-O",
+            "linenos=true,linenostart=10,linenostep=5",
+        ]
+    );
+}
+
+#[test]
+fn latex_line_number_start() {
+    let mut fmt = LatexFormatter {
+        line_number_start: Some(10),
+        ..LatexFormatter::default()
+    };
+
+    // Only passed if line numbers are enabled
+    assert_eq!(
+        args("", Some("rust"), &fmt).unwrap(),
+        ["-f", "latex", "-l", "rust"]
+    );
+
+    fmt.line_numbers = true;
+    assert_eq!(
+        args("", Some("rust"), &fmt).unwrap(),
+        [
+            "-f",
+            "latex",
+            "-l",
+            "rust",
+            "-O",
+            "linenos=true,linenostart=10"
+        ]
+    );
+}
+
+#[test]
+fn verbatim_options() {
+    let fmt = LatexFormatter {
+        full: true,
+        preamble: Some(String::from(r"\usepackage[a,b=c]{x}")),
+        ..LatexFormatter::default()
+    };
+
+    let args = args("", Some("rust"), &fmt).unwrap();
+    assert_eq!(
+        args,
+        [
+            "-f",
+            "latex",
+            "-l",
+            "rust",
+            "-O",
+            "full=true",
+            "-P",
+            r"preamble=\usepackage[a,b=c]{x}",
+        ]
+    );
+}
+
+#[test]
+fn style() {
+    let fmt = Terminal256Formatter {
+        line_numbers: true,
+        style: Some(String::from("monokai")),
+        ..Terminal256Formatter::default()
+    };
+
+    let args = args("", Some("rust"), &fmt).unwrap();
+    assert_eq!(
+        args,
+        [
+            "-f",
+            "terminal256",
+            "-l",
+            "rust",
+            "-O",
+            "linenos=true,style=monokai",
+        ]
+    );
+}
+
+#[test]
+fn invalid_option() {
+    let fmt = LatexFormatter {
+        line_numbers: true,
+        line_number_start: Some(0),
+        ..LatexFormatter::default()
+    };
+
+    let res = args("", Some("rust"), &fmt);
+    assert!(matches!(
+        res,
+        Err(PygmentizeError::InvalidOption("line_number_start", _))
+    ));
+}
+
+#[test]
+fn options() {
+    let mut fmt = TerminalFormatter {
+        line_numbers: true,
+        ..TerminalFormatter::default()
+    };
+    fmt.options.insert("bg".into(), "dark".into());
+    fmt.options.insert("linenos".into(), "false".into());
+
+    let args = args("", Some("rust"), &fmt).unwrap();
+    assert_eq!(
+        args,
+        [
+            "-f",
+            "terminal",
+            "-l",
+            "rust",
+            "-O",
+            "bg=dark,linenos=false"
+        ]
+    );
+
+    fmt.options.insert("hl_lines".into(), "1,2".into());
+    let res = pygmentize::highlight("", Some("rust"), &fmt);
+    assert!(matches!(
+        res,
+        Err(PygmentizeError::InvalidOption("options", _))
+    ));
+}
+
+#[test]
+fn exit_status() {
+    let res = args(
+        "exit 3 unknown lexer",
+        Some("rust"),
+        &HtmlFormatter::default(),
+    );
+    match res {
+        Err(PygmentizeError::Pygmentize(status, stderr)) => {
+            assert_eq!(status.code(), Some(3));
+            assert_eq!(stderr, "unknown lexer\n");
+        }
+        res => panic!("expected `PygmentizeError::Pygmentize`, got {res:?}"),
+    }
+}
+
+#[test]
+fn exit_status_writer() {
+    setup();
+
+    let mut out = Vec::new();
+    let res = pygmentize::highlight_to_writer(
+        "exit 3 unknown lexer",
+        Some("rust"),
+        &HtmlFormatter::default(),
+        &mut out,
+    );
+    match res {
+        Err(PygmentizeError::Pygmentize(status, stderr)) => {
+            assert_eq!(status.code(), Some(3));
+            assert_eq!(stderr, "unknown lexer\n");
+        }
+        res => panic!("expected `PygmentizeError::Pygmentize`, got {res:?}"),
+    }
+}
+
+#[test]
+fn unknown_lexer_and_formatter() {
+    let res = args(
+        "exit 1 Error: no lexer for alias 'nope' found",
+        Some("rust"),
+        &HtmlFormatter::default(),
+    );
+    assert!(matches!(res, Err(PygmentizeError::UnknownLexer(lang)) if lang == "nope"));
+
+    let res = args(
+        "exit 1 Error: no formatter found for name 'nope'",
+        Some("rust"),
+        &HtmlFormatter::default(),
+    );
+    assert!(matches!(res, Err(PygmentizeError::UnknownFormatter(fmt)) if fmt == "nope"));
+}
+
+#[test]
+fn smart_unknown_lexer() {
+    setup();
+
+    // Detected as `unknown`, for which no lexer exists, so it is guessed
+    let code = "#!/usr/bin/env unknown3.1\nputs 1";
+    let output = pygmentize::highlight_smart(code, &HtmlFormatter::default()).unwrap();
+    let args = output.lines().collect::<Vec<_>>();
+    assert_eq!(args, ["-f", "html", "-g"]);
+}
+
+#[test]
+fn list_lexers() {
+    setup();
+
+    let lexers = pygmentize::list_lexers().unwrap();
+    let names = lexers
+        .iter()
+        .map(|lexer| lexer.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["ABAP", "Caf\u{e9} \"Script\" \u{1f980}", "Rust"]);
+
+    assert_eq!(lexers[0].mimetypes, Vec::<String>::new());
+    assert_eq!(lexers[1].filenames, [r"*.caf\e"]);
+    assert_eq!(lexers[1].mimetypes, ["text/x-caf\u{e9}"]);
+    assert_eq!(lexers[2].aliases, ["rust", "rs"]);
+    assert_eq!(lexers[2].filenames, ["*.rs", "*.rs.in"]);
+    assert_eq!(lexers[2].mimetypes, ["text/rust", "text/x-rust"]);
+}
+
+#[test]
+fn list_styles() {
+    setup();
+
+    let styles = pygmentize::list_styles().unwrap();
+    assert_eq!(styles, ["arduino", "default", "monokai"]);
+}
+
+#[test]
+fn invalid_utf8() {
+    let res = args("invalid-utf8", Some("rust"), &HtmlFormatter::default());
+    assert!(matches!(res, Err(PygmentizeError::InvalidUtf8(_))));
+}
+
+#[test]
+fn large_input() {
+    setup();
+
+    // Larger than the pipe buffers, such that writing stdin would block,
+    // if stdout was not read concurrently
+    let code = "fn main() {}\n".repeat(1 << 20);
+    let output = pygmentize::highlight(&code, Some("echo"), &HtmlFormatter::default()).unwrap();
+    assert_eq!(output, code);
+}
+
+#[test]
+fn timeout() {
+    let pygmentize = Pygmentize::new()
+        .bin_path(MOCK)
+        .timeout(Duration::from_millis(100));
+
+    let start = Instant::now();
+    let res = pygmentize.highlight("", Some("sleep"), &HtmlFormatter::default());
+    assert!(matches!(res, Err(PygmentizeError::Timeout)));
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    // The input is not read by the mock, so writing it must not block
+    let code = "x".repeat(1 << 20);
+    let res = pygmentize.highlight(code, Some("sleep"), &HtmlFormatter::default());
+    assert!(matches!(res, Err(PygmentizeError::Timeout)));
+}
+
+#[test]
+fn highlight_bytes() {
+    setup();
+
+    let output =
+        pygmentize::highlight_bytes("invalid-utf8", Some("rust"), &HtmlFormatter::default());
+    assert_eq!(output.unwrap(), b"\xff\xfe");
+
+    let fmt = ImageFormatter {
+        font_size: Some(24),
+        image_format: Some(ImageFormat::Gif),
+        ..ImageFormatter::default()
+    };
+    let output = pygmentize::highlight_bytes("", Some("rust"), &fmt).unwrap();
+    assert_eq!(
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .collect::<Vec<_>>(),
+        [
+            "-f",
+            "png",
+            "-l",
+            "rust",
+            "-O",
+            "font_size=24,image_format=gif,line_numbers=false",
+        ]
+    );
+}
+
+#[test]
+fn already_highlighted() {
+    let fmt = HtmlFormatter {
+        reject_highlighted: true,
+        ..HtmlFormatter::default()
+    };
+
+    let res = args(r#"<span class="k">fn</span>"#, Some("html"), &fmt);
+    assert!(matches!(res, Err(PygmentizeError::AlreadyHighlighted)));
+}
+
+#[test]
+fn version() {
+    setup();
+    let version = pygmentize::version().unwrap();
+    assert_eq!(version.to_string(), "2.99.1");
+}
+
+#[test]
+fn is_available() {
+    setup();
+    assert!(pygmentize::is_available());
+}
@@ -0,0 +1,32 @@
+//! Separate from `tests/mock.rs`, as the binary path is global.
+
+use std::ffi::OsStr;
+
+use pygmentize::{HtmlFormatter, PygmentizeError};
+
+#[test]
+fn not_found() {
+    pygmentize::set_bin_path(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/mock/missing"));
+
+    let res = pygmentize::highlight("", Some("rust"), &HtmlFormatter::default());
+    assert!(matches!(res, Err(PygmentizeError::NotFound(_))));
+
+    assert!(!pygmentize::is_available());
+}
+
+#[cfg(unix)]
+#[test]
+fn with_bin() {
+    pygmentize::set_bin_path(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/mock/missing"));
+
+    let mock = OsStr::new(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/mock/pygmentize"
+    ));
+    let output =
+        pygmentize::highlight_with_bin(mock, "", Some("rust"), &HtmlFormatter::default()).unwrap();
+    assert_eq!(
+        output.lines().collect::<Vec<_>>(),
+        ["-f", "html", "-l", "rust"]
+    );
+}
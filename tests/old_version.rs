@@ -0,0 +1,43 @@
+//! Separate from `tests/mock.rs`, as the binary paths are global.
+
+#![cfg(unix)]
+
+use pygmentize::Version;
+
+const MOCK: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/mock/pygmentize-old");
+
+#[test]
+fn pre_release_version() {
+    pygmentize::set_bin_path(MOCK);
+
+    // i.e. `2.10.0rc1`
+    assert_eq!(pygmentize::version().unwrap(), Version::new(2, 10, 0));
+    assert!(pygmentize::require_min_version(Version::new(2, 10, 0)).is_ok());
+}
+
+#[test]
+fn list_without_json() {
+    pygmentize::set_bin_path(MOCK);
+
+    // `-L lexers --json` requires Pygments 2.11, i.e. the plain output is parsed
+    let lexers = pygmentize::list_lexers().unwrap();
+    let names = lexers
+        .iter()
+        .map(|lexer| lexer.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["ABAP", "Raw token data", "Rust", "Text only"]);
+
+    assert_eq!(lexers[0].aliases, ["abap"]);
+    assert_eq!(lexers[0].filenames, ["*.abap", "*.ABAP"]);
+    assert_eq!(lexers[1].aliases, Vec::<String>::new());
+    assert_eq!(lexers[1].filenames, Vec::<String>::new());
+    assert_eq!(lexers[2].aliases, ["rust", "rs"]);
+    assert_eq!(lexers[2].filenames, ["*.rs", "*.rs.in"]);
+    assert!(lexers.iter().all(|lexer| lexer.mimetypes.is_empty()));
+
+    assert!(pygmentize::is_valid_lexer("RS").unwrap());
+    assert!(!pygmentize::is_valid_lexer("python").unwrap());
+
+    let styles = pygmentize::list_styles().unwrap();
+    assert_eq!(styles, ["arduino", "default", "monokai"]);
+}
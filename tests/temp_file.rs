@@ -0,0 +1,38 @@
+//! Separate from `tests/mock.rs`, as the setting is global.
+
+#![cfg(unix)]
+
+use std::path::Path;
+
+use pygmentize::HtmlFormatter;
+
+#[test]
+fn temp_file() {
+    pygmentize::set_bin_path(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/mock/pygmentize"
+    ));
+    pygmentize::set_temp_file_threshold(Some(8));
+
+    // Below the threshold, the code is written to stdin
+    let output = pygmentize::highlight("x", Some("rust"), &HtmlFormatter::default()).unwrap();
+    assert_eq!(
+        output.lines().collect::<Vec<_>>(),
+        ["-f", "html", "-l", "rust"]
+    );
+
+    let output =
+        pygmentize::highlight("let x = 1;", Some("rust"), &HtmlFormatter::default()).unwrap();
+    let args = output.lines().collect::<Vec<_>>();
+    assert_eq!(args[..4], ["-f", "html", "-l", "rust"]);
+
+    let path = Path::new(args[4]);
+    assert!(path.starts_with(std::env::temp_dir()));
+    assert!(!path.exists(), "expected {} to be removed", path.display());
+
+    // Only accessible by the current user
+    let output =
+        pygmentize::highlight("let x = 1;", Some("permissions"), &HtmlFormatter::default())
+            .unwrap();
+    assert_eq!(output.trim_end(), "-rw-------");
+}
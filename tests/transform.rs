@@ -0,0 +1,23 @@
+//! Separate from `tests/mock.rs`, as the transforms are global.
+
+#![cfg(unix)]
+
+use pygmentize::HtmlFormatter;
+
+#[test]
+fn tokenize_skips_transforms() {
+    pygmentize::set_bin_path(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/mock/pygmentize"
+    ));
+    pygmentize::add_transform(|output| format!("{output}transformed\n"));
+
+    // The `echo` language outputs the code, i.e. the raw tokens, as is
+    let tokens = pygmentize::tokenize("Token.Text\t'x'\n", Some("echo")).unwrap();
+    assert_eq!(tokens.len(), 1);
+    assert_eq!(tokens[0].0.as_str(), "Token.Text");
+    assert_eq!(tokens[0].1, "x");
+
+    let html = pygmentize::highlight("x", Some("echo"), &HtmlFormatter::default()).unwrap();
+    assert!(html.ends_with("transformed\n"));
+}
@@ -0,0 +1,52 @@
+//! Separate from `tests/mock.rs`, as the binary paths and
+//! language validation are global.
+
+#![cfg(unix)]
+
+use pygmentize::{HtmlFormatter, Pygmentize, PygmentizeError, Version};
+
+const MOCK: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/mock/pygmentize");
+const MISSING: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/mock/missing");
+
+#[test]
+fn validate_language() {
+    pygmentize::set_validate_language(true);
+
+    // The lexers of the mock are listed in `tests/mock/lexers.json`
+    pygmentize::set_bin_path(MOCK);
+    assert!(pygmentize::is_valid_lexer("rust").unwrap());
+    assert!(!pygmentize::is_valid_lexer("python").unwrap());
+
+    // The cached lexers are not used for another binary
+    pygmentize::set_bin_path(MISSING);
+    let res = pygmentize::is_valid_lexer("rust");
+    assert!(matches!(res, Err(PygmentizeError::NotFound(_))));
+
+    // The lexers are listed using the binary of the call
+    let pygmentize = Pygmentize::new().bin_path(MOCK);
+    let fmt = HtmlFormatter::default();
+
+    let args = pygmentize.highlight("", Some("rust"), &fmt).unwrap();
+    assert_eq!(args, "-f\nhtml\n-l\nrust\n");
+
+    let res = pygmentize.highlight("", Some("python"), &fmt);
+    assert!(matches!(res, Err(PygmentizeError::UnknownLexer(lang)) if lang == "python"));
+
+    // Post-processing also uses the binary of the call
+    let fmt = HtmlFormatter {
+        version_comment: true,
+        scoped_style: true,
+        ..HtmlFormatter::default()
+    };
+    let output = pygmentize.highlight("", Some("rust"), &fmt).unwrap();
+    assert!(output.starts_with("<!-- pygments 2.99.1 -->\n<style>\n"));
+
+    // As do the other functions of the instance
+    assert_eq!(pygmentize.version().unwrap(), Version::new(2, 99, 1));
+    assert_eq!(pygmentize.list_lexers().unwrap().len(), 3);
+    assert!(pygmentize.is_valid_lexer("rs").unwrap());
+    assert_eq!(
+        pygmentize.get_style_defs("monokai", None).unwrap(),
+        "-S\nmonokai\n-f\nhtml\n"
+    );
+}
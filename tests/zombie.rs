@@ -0,0 +1,32 @@
+//! Separate from `tests/mock.rs`, as checking for zombies
+//! would otherwise reap the children of other tests.
+
+#![cfg(unix)]
+
+use pygmentize::{HtmlFormatter, PygmentizeError};
+
+#[test]
+fn no_zombie_on_stdin_write_failure() {
+    pygmentize::set_bin_path(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/mock/pygmentize"
+    ));
+
+    // Larger than the pipe buffer, such that writing
+    // fails as `pygmentize` exits without reading stdin
+    let code = "x".repeat(1 << 20);
+
+    let res = pygmentize::highlight(&code, Some("exit-early"), &HtmlFormatter::default());
+    match res {
+        Err(PygmentizeError::UnknownLexer(lang)) => assert_eq!(lang, "exit-early"),
+        res => panic!("expected `PygmentizeError::UnknownLexer`, got {res:?}"),
+    }
+
+    // If the child was reaped, then there are no children left
+    let pid = unsafe { libc::waitpid(-1, std::ptr::null_mut(), libc::WNOHANG) };
+    assert_eq!(pid, -1, "expected no child processes, found {pid}");
+    assert_eq!(
+        std::io::Error::last_os_error().raw_os_error(),
+        Some(libc::ECHILD)
+    );
+}